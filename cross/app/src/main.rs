@@ -3,7 +3,6 @@
 
 //USB serial console (minicom -b 115200 -o -D /dev/ttyACM0)
 
-mod keyboard;
 mod logger;
 mod neopixel;
 mod oled_display;
@@ -32,12 +31,13 @@ use embedded_time::duration::Extensions;
 use embedded_time::fixed_point::FixedPoint;
 use embedded_time::rate::Hertz;
 use keyboard::keycode::KeyCode;
+use keyboard::report::{BOOT_REPORT_LEN, NKRO_REPORT_LEN};
 use keyboard::Keyboard;
 use log::{info, LevelFilter};
 use rp2040_hal::gpio::dynpin::DynPin;
 use sh1106::{prelude::*, Builder};
 use usb_device::class_prelude::*;
-use usbd_hid::descriptor::KeyboardReport;
+use usbd_hid::descriptor::{MediaKeyboardReport, MouseReport};
 use ws2812_pio::Ws2812;
 
 type Spi = rp2040_hal::spi::Spi<rp2040_hal::spi::Enabled, rp2040_hal::pac::SPI1, 8_u8>;
@@ -175,13 +175,27 @@ fn main() -> ! {
 
     info!("macropad starting");
 
-    let rot_pin_a =
-        debounce::DebouncedPin::<DynPin>::new(pins.encoder_rota.into_pull_up_input().into(), true);
-    let rot_pin_b =
-        debounce::DebouncedPin::<DynPin>::new(pins.encoder_rotb.into_pull_up_input().into(), true);
+    let rot_pin_a = debounce::DebouncedPin::<DynPin>::with_algorithm(
+        pins.encoder_rota.into_pull_up_input().into(),
+        debounce::DebouncerState::Unknown,
+        debounce::Algorithm::default(),
+    );
+    let rot_pin_b = debounce::DebouncedPin::<DynPin>::with_algorithm(
+        pins.encoder_rotb.into_pull_up_input().into(),
+        debounce::DebouncerState::Unknown,
+        debounce::Algorithm::default(),
+    );
 
     let mut rot_enc = rotary_enc::RotaryEncoder::new(rot_pin_a, rot_pin_b);
 
+    //pressing the encoder toggles the switch diagnostics screen
+    let mut rot_button = debounce::DebouncedPin::<DynPin>::with_algorithm(
+        pins.button.into_pull_up_input().into(),
+        debounce::DebouncerState::Unknown,
+        debounce::Algorithm::default(),
+    );
+    let mut show_diagnostics = false;
+
     let pins: [DynPin; 12] = [
         pins.key1.into_pull_up_input().into(),
         pins.key2.into_pull_up_input().into(),
@@ -197,29 +211,133 @@ fn main() -> ! {
         pins.key12.into_pull_up_input().into(),
     ];
 
-    //keypad, final row: '0', '.', 'enter'
-    const KEY_MAP: [keyboard::KeyAction; 12] = [
-        keyboard::KeyAction::Key { code: KeyCode::Kp7 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp8 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp9 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp4 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp5 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp6 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp1 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp2 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp3 },
-        keyboard::KeyAction::Key { code: KeyCode::Kp0 },
-        keyboard::KeyAction::Key {
-            code: KeyCode::KpDot,
-        },
-        keyboard::KeyAction::Key {
-            code: KeyCode::KpEnter,
-        },
+    use keyboard::keycode::Modifiers;
+    use keyboard::KeyAction::{
+        Consumer, Leader, Macro, MomentaryLayer, Mouse, OneShotMod, System, TapDance, TapHold,
+        ToLayer, ToggleLayer, Transparent, Unicode,
+    };
+    use keyboard::{consumer, system, MacroStep, MouseAction};
+    const fn key(code: KeyCode) -> keyboard::KeyAction {
+        keyboard::KeyAction::key(code)
+    }
+    const DOT_LAYER: keyboard::TapDance =
+        keyboard::TapDance::new(&[key(KeyCode::KpDot), key(KeyCode::Comma)])
+            .hold(MomentaryLayer(1));
+    const ENTER_CTRL: keyboard::TapHold =
+        keyboard::TapHold::new(key(KeyCode::KpEnter), key(KeyCode::LeftControl)).permissive_hold();
+    //format the document from the editor's command palette
+    const FORMAT: &[MacroStep] = &[
+        MacroStep::Press(KeyCode::LeftControl),
+        MacroStep::Press(KeyCode::LeftShift),
+        MacroStep::Tap(KeyCode::P),
+        MacroStep::Release(KeyCode::LeftShift),
+        MacroStep::Release(KeyCode::LeftControl),
+        MacroStep::Delay(100),
+        MacroStep::Text("format"),
+        MacroStep::Tap(KeyCode::Enter),
+    ];
+
+    //open the task manager
+    const TASK_MANAGER: &[MacroStep] = &[
+        MacroStep::Press(KeyCode::LeftControl),
+        MacroStep::Press(KeyCode::LeftShift),
+        MacroStep::Tap(KeyCode::Escape),
+        MacroStep::Release(KeyCode::LeftShift),
+        MacroStep::Release(KeyCode::LeftControl),
+    ];
+
+    //layer 0 keypad, final row: '0', '.' or ',' tapped twice or hold for layer 1, 'enter' or hold for 'ctrl'
+    //layer 1 media, toggles editor layer 2, one-shot 'ctrl' or returns to the keypad,
+    //final row: '→', leader
+    //layer 2 editor navigation, final row: format document, one-shot 'shift'
+    //layer 3 mouse, keypad arrows move the pointer, '5' '9' '3' left, right and middle buttons,
+    //'7' '1' scroll, final row: back to the keypad
+    //one-shots apply to the next key, tap twice to lock
+    const KEY_MAP: [[keyboard::KeyAction; 12]; 4] = [
+        [
+            key(KeyCode::Kp7),
+            key(KeyCode::Kp8),
+            key(KeyCode::Kp9),
+            key(KeyCode::Kp4),
+            key(KeyCode::Kp5),
+            key(KeyCode::Kp6),
+            key(KeyCode::Kp1),
+            key(KeyCode::Kp2),
+            key(KeyCode::Kp3),
+            key(KeyCode::Kp0),
+            TapDance(&DOT_LAYER),
+            TapHold(&ENTER_CTRL),
+        ],
+        [
+            Consumer(consumer::MUTE),
+            Consumer(consumer::VOLUME_DOWN),
+            Consumer(consumer::VOLUME_UP),
+            Consumer(consumer::PREVIOUS_TRACK),
+            Consumer(consumer::PLAY_PAUSE),
+            Consumer(consumer::NEXT_TRACK),
+            ToggleLayer(2),
+            OneShotMod(Modifiers::CTRL_LEFT),
+            ToLayer(0),
+            Unicode('→'),
+            Transparent,
+            Leader,
+        ],
+        [
+            key(KeyCode::Home),
+            key(KeyCode::UpArrow),
+            key(KeyCode::PageUp),
+            key(KeyCode::LeftArrow),
+            key(KeyCode::DownArrow),
+            key(KeyCode::RightArrow),
+            key(KeyCode::End),
+            key(KeyCode::Backspace),
+            key(KeyCode::PageDown),
+            Macro(FORMAT),
+            Transparent,
+            OneShotMod(Modifiers::SHIFT_LEFT),
+        ],
+        [
+            Mouse(MouseAction::WheelUp),
+            Mouse(MouseAction::Up),
+            Mouse(MouseAction::Button(keyboard::BUTTON_RIGHT)),
+            Mouse(MouseAction::Left),
+            Mouse(MouseAction::Button(keyboard::BUTTON_LEFT)),
+            Mouse(MouseAction::Right),
+            Mouse(MouseAction::WheelDown),
+            Mouse(MouseAction::Down),
+            Mouse(MouseAction::Button(keyboard::BUTTON_MIDDLE)),
+            ToggleLayer(3),
+            Transparent,
+            Transparent,
+        ],
+    ];
+
+    //chords on the keypad's top row: '7'+'8' backspace, '8'+'9' escape
+    const COMBOS: &[keyboard::Combo] = &[
+        keyboard::Combo::new(&[0, 1], key(KeyCode::Backspace)).layers(1 << 0),
+        keyboard::Combo::new(&[1, 2], key(KeyCode::Escape)).layers(1 << 0),
     ];
 
+    //sequences typed after the leader: '1' '2' task manager, '0' format document, '9' '9' sleep,
+    //'5' mouse layer
+    const LEADERS: &[keyboard::Leader] = &[
+        keyboard::Leader::new(&[KeyCode::Kp1, KeyCode::Kp2], Macro(TASK_MANAGER)),
+        keyboard::Leader::new(&[KeyCode::Kp0], Macro(FORMAT)),
+        keyboard::Leader::new(&[KeyCode::Kp9, KeyCode::Kp9], System(system::SLEEP)),
+        keyboard::Leader::new(&[KeyCode::Kp5], ToggleLayer(3)),
+    ];
+
+    //eager debouncing reports key presses on the first scan they are seen, timed in ms so
+    //a slow loop iteration does not stretch the debounce window
     let mut keyboard = Keyboard::new(
-        keyboard::DirectPinMatrix::new(pins),
-        keyboard::BasicKeyboardLayout::new(KEY_MAP),
+        keyboard::DirectPinMatrix::with_algorithm(
+            pins,
+            debounce::Algorithm::TimedEager { stable: 5 },
+        ),
+        keyboard::ComboLayout::new(
+            keyboard::LayeredKeyboardLayout::new(KEY_MAP).leaders(LEADERS),
+            COMBOS,
+        ),
     );
 
     let mut fast_countdown = timer.count_down();
@@ -228,39 +346,162 @@ fn main() -> ! {
     let mut slow_countdown = timer.count_down();
     slow_countdown.start(20.milliseconds());
 
+    //report waiting for the usb endpoint, taps and macros need every report delivered
+    //boot and NKRO encodings of the same state, the usb manager sends whichever the host uses
+    let mut keyboard_report: Option<([u8; BOOT_REPORT_LEN], [u8; NKRO_REPORT_LEN])> = None;
+    let mut consumer_report: Option<MediaKeyboardReport> = None;
+    let mut consumer_usage = 0;
+    let mut system_report: Option<u8> = None;
+    let mut system_usage = 0;
+    let mut mouse_report: Option<MouseReport> = None;
+    //encoder position last turned into volume steps
+    let mut enc_value = rot_enc.value();
+
     info!("Running main loop");
 
     loop {
         //1ms scan the keys and debounce
         if fast_countdown.wait().is_ok() {
             let (p_a, p_b) = rot_enc.pins_borrow_mut();
-            p_a.update().expect("Failed to update rot a debouncer");
-            p_b.update().expect("Failed to update rot b debouncer");
+            let edge_a = p_a.update().expect("Failed to update rot a debouncer").edge;
+            let edge_b = p_b.update().expect("Failed to update rot b debouncer").edge;
+            let settled = p_a.is_settled() && p_b.is_settled();
             //todo: move onto an interupt timer
-            rot_enc.update();
+            if settled && (edge_a != debounce::Edge::None || edge_b != debounce::Edge::None) {
+                rot_enc.update();
+            }
+
+            let now_ms = (timer.get_counter() / 1000) as u32;
+            keyboard.update(now_ms).expect("Failed to update keyboard");
+
+            //each encoder step taps volume up or down
+            let volume = match rot_enc.value().cmp(&enc_value) {
+                core::cmp::Ordering::Greater => Some(consumer::VOLUME_UP),
+                core::cmp::Ordering::Less => Some(consumer::VOLUME_DOWN),
+                core::cmp::Ordering::Equal => None,
+            };
+            if let Some(usage) = volume {
+                for pressed in [true, false] {
+                    keyboard.trigger(keyboard::KeyAction::Consumer(usage), pressed);
+                }
+                enc_value += if usage == consumer::VOLUME_UP { 1 } else { -1 };
+            }
+
+            let reports_sent = keyboard_report.is_none()
+                && consumer_report.is_none()
+                && system_report.is_none()
+                && mouse_report.is_none();
+            if reports_sent && keyboard.has_report() {
+                let keyboard_state = keyboard.next_state().expect("Failed to get Keyboard state");
+                keyboard_report =
+                    Some((keyboard_state.boot_report(), keyboard_state.nkro_report()));
+                if keyboard_state.consumer != consumer_usage {
+                    consumer_usage = keyboard_state.consumer;
+                    consumer_report = Some(MediaKeyboardReport {
+                        usage_id: consumer_usage,
+                    });
+                }
+                if keyboard_state.system != system_usage {
+                    system_usage = keyboard_state.system;
+                    system_report = Some(system_usage);
+                }
+            }
+            if let Some((boot, nkro)) = &keyboard_report {
+                let sent = cortex_m::interrupt::free(|cs| {
+                    let mut usb_ref = USB_MANAGER.borrow(cs).borrow_mut();
+                    usb_ref
+                        .as_mut()
+                        .is_some_and(|usb| report_done(usb.push_keyboard_report(boot, nkro)))
+                });
+                if sent {
+                    keyboard_report = None;
+                }
+            }
+            if let Some(report) = &consumer_report {
+                let sent = cortex_m::interrupt::free(|cs| {
+                    let mut usb_ref = USB_MANAGER.borrow(cs).borrow_mut();
+                    usb_ref.as_mut().is_some_and(|usb| {
+                        report_done(usb.consumer_borrow_mut().push_input(report))
+                    })
+                });
+                if sent {
+                    consumer_report = None;
+                }
+            }
+            if let Some(usage) = system_report {
+                let sent = cortex_m::interrupt::free(|cs| {
+                    let mut usb_ref = USB_MANAGER.borrow(cs).borrow_mut();
+                    usb_ref.as_mut().is_some_and(|usb| {
+                        report_done(usb.system_borrow_mut().push_raw_input(&[usage]))
+                    })
+                });
+                if sent {
+                    system_report = None;
+                }
+            }
+            //mouse movement accumulates until the last report is taken
+            if mouse_report.is_none() {
+                mouse_report = keyboard.mouse_report().map(|mouse| MouseReport {
+                    buttons: mouse.buttons,
+                    x: mouse.x,
+                    y: mouse.y,
+                    wheel: mouse.wheel,
+                    pan: 0,
+                });
+            }
+            if let Some(report) = &mouse_report {
+                let sent = cortex_m::interrupt::free(|cs| {
+                    let mut usb_ref = USB_MANAGER.borrow(cs).borrow_mut();
+                    usb_ref
+                        .as_mut()
+                        .is_some_and(|usb| report_done(usb.mouse_borrow_mut().push_input(report)))
+                });
+                if sent {
+                    mouse_report = None;
+                }
+            }
 
-            keyboard.update().expect("Failed to update keyboard");
+            let button = rot_button.update().expect("Failed to update rot button");
+            if button.edge == debounce::Edge::Falling {
+                show_diagnostics = !show_diagnostics;
+                if show_diagnostics {
+                    for (i, s) in keyboard.matrix().stats().iter().enumerate() {
+                        info!(
+                            "key {}: {} bounces, {} transitions, longest burst {}",
+                            i + 1,
+                            s.bounces,
+                            s.transitions,
+                            s.longest_burst
+                        );
+                    }
+                }
+            }
         }
 
         //10ms
         if slow_countdown.wait().is_ok() {
             //100Hz or slower
             let keyboard_state = keyboard.state().expect("Failed to get Keyboard state");
-            let keyboard_report = get_hid_report(&keyboard_state);
-
-            //todo - spin lock until usb ready to recive, reset timers
-            cortex_m::interrupt::free(|cs| {
-                let mut usb_ref = USB_MANAGER.borrow(cs).borrow_mut();
-                if let Some(usb) = usb_ref.as_mut() {
-                    usb.keyboard_borrow_mut().push_input(&keyboard_report).ok();
-                }
-            });
 
             //update the screen
             cortex_m::interrupt::free(|cs| {
                 let mut oled_display_ref = OLED_DISPLAY.borrow(cs).borrow_mut();
                 if let Some(oled_display) = oled_display_ref.as_mut() {
-                    oled_display.draw_numpad(rot_enc.value()).unwrap();
+                    if show_diagnostics {
+                        oled_display
+                            .draw_diagnostics(&keyboard.matrix().stats())
+                            .unwrap();
+                    } else if let Some(leader) = &keyboard_state.leader {
+                        oled_display.draw_leader(leader).unwrap();
+                    } else {
+                        oled_display
+                            .draw_numpad(
+                                keyboard.layout().layout().active_layer(),
+                                keyboard_state.one_shots,
+                                rot_enc.value(),
+                            )
+                            .unwrap();
+                    }
                 }
             });
 
@@ -278,30 +519,10 @@ fn main() -> ! {
     }
 }
 
-fn get_hid_report<const N: usize>(state: &keyboard::KeyboardState<N>) -> KeyboardReport {
-    //get first 6 current keypresses and send to usb
-    let mut keycodes: [u8; 6] = [0, 0, 0, 0, 0, 0];
-
-    let mut keycodes_it = keycodes.iter_mut();
-
-    for k in &state.keycodes {
-        match keycodes_it.next() {
-            Some(kc) => {
-                *kc = *k as u8;
-            }
-            None => {
-                keycodes.fill(0x01); //Error roll over
-                break;
-            }
-        }
-    }
-
-    KeyboardReport {
-        modifier: state.modifiers.bits(),
-        leds: 0,
-        reserved: 0,
-        keycodes,
-    }
+//a report is done with once sent, or once it fails for any reason but a busy endpoint, so one
+//the host can't take doesn't hold up the others
+fn report_done(result: usb_device::Result<usize>) -> bool {
+    !matches!(result, Err(UsbError::WouldBlock))
 }
 
 #[allow(non_snake_case)]
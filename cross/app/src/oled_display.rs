@@ -14,6 +14,8 @@ use embedded_text::{
     style::{HeightMode, TextBoxStyleBuilder},
     TextBox,
 };
+use keyboard::keycode::{KeyCode, Modifiers};
+use keyboard::OneShots;
 use sh1106::interface::DisplayInterface;
 use sh1106::prelude::GraphicsMode;
 
@@ -58,14 +60,77 @@ impl<DI: DisplayInterface> OledDisplay<DI> {
         Ok(())
     }
 
-    pub fn draw_numpad(&mut self, enc_value: i32) -> Result<(), DI::Error> {
+    pub fn draw_numpad(
+        &mut self,
+        layer: u8,
+        one_shots: OneShots,
+        enc_value: i32,
+    ) -> Result<(), DI::Error> {
+        let keys = match layer {
+            0 => "7 8 9\n4 5 6\n1 2 3\n0 . E",
+            1 => "Mut Vl- Vl+\nPrv Ply Nxt\nEdt Ctl Num\n->   .  Ldr",
+            2 => "Hom Up  PgU\nLft Dn  Rgt\nEnd Bsp PgD\nFmt  .  Sft",
+            _ => "WhU Up  RBt\nLft LBt Rgt\nWhD Dn  MBt\nNum  .   E",
+        };
         let mut output = arrayvec::ArrayString::<256>::new();
         write!(
             &mut output,
-            "7 8 9\n4 5 6\n1 2 3\n0 . E\nEnc: {}",
-            enc_value
+            "{}\nLayer: {}\nEnc: {}",
+            keys, layer, enc_value
         )
         .unwrap();
+        if !one_shots.is_empty() {
+            output.push_str("\nOne-shot:");
+            Self::write_one_shots(&mut output, one_shots.modifiers, one_shots.layers, "");
+            Self::write_one_shots(
+                &mut output,
+                one_shots.locked_modifiers,
+                one_shots.locked_layers,
+                "*",
+            );
+        }
+        self.draw_text_screen(output.as_str())
+    }
+
+    //short names for one-shot modifiers and layers, each followed by a marker
+    fn write_one_shots(output: &mut impl Write, modifiers: Modifiers, layers: u32, marker: &str) {
+        const NAMES: [&str; 8] = ["Ctl", "Sft", "Alt", "Gui", "RCtl", "RSft", "RAlt", "RGui"];
+        for (i, name) in NAMES.iter().enumerate() {
+            if modifiers.bits() & 1 << i != 0 {
+                write!(output, " {}{}", name, marker).ok();
+            }
+        }
+        for layer in (0..32).filter(|l| layers & 1 << l != 0) {
+            write!(output, " L{}{}", layer, marker).ok();
+        }
+    }
+
+    /// Keys typed so far after the leader key
+    pub fn draw_leader(&mut self, keys: &[KeyCode]) -> Result<(), DI::Error> {
+        let mut output = arrayvec::ArrayString::<256>::new();
+        output.push_str("Leader:\n");
+        for code in keys {
+            write!(&mut output, "{:?} ", code).ok();
+        }
+        self.draw_text_screen(output.as_str())
+    }
+
+    pub fn draw_diagnostics(&mut self, stats: &[debounce::Stats]) -> Result<(), DI::Error> {
+        let mut output = arrayvec::ArrayString::<512>::new();
+        output.push_str("key bounces/presses/burst\n");
+        for (i, s) in stats.iter().enumerate() {
+            let separator = if i % 2 == 0 { "  " } else { "\n" };
+            write!(
+                &mut output,
+                "{:>2} {:>4}/{:>4}/{:>2}{}",
+                i + 1,
+                s.bounces,
+                s.transitions / 2,
+                s.longest_burst,
+                separator
+            )
+            .ok();
+        }
         self.draw_text_screen(output.as_str())
     }
 
@@ -1,8 +1,13 @@
 use usb_device::class_prelude::*;
 use usb_device::prelude::*;
 use usbd_hid::descriptor::KeyboardReport;
+use usbd_hid::descriptor::MediaKeyboardReport;
+use usbd_hid::descriptor::MouseReport;
 use usbd_hid::descriptor::SerializedDescriptor;
-use usbd_hid::hid_class::HIDClass;
+use usbd_hid::hid_class::{
+    HIDClass, HidClassSettings, HidCountryCode, HidProtocol, HidProtocolMode, HidSubClass,
+    ProtocolModeConfig,
+};
 use usbd_serial::SerialPort;
 
 pub struct UsbManager<'a, B>
@@ -12,6 +17,10 @@ where
     usb_device: UsbDevice<'a, B>,
     serial_port: SerialPort<'a, B>,
     keyboard: HIDClass<'a, B>,
+    boot_keyboard: HIDClass<'a, B>,
+    consumer: HIDClass<'a, B>,
+    system: HIDClass<'a, B>,
+    mouse: HIDClass<'a, B>,
 }
 
 impl<'a, B> UsbManager<'a, B>
@@ -20,7 +29,27 @@ where
 {
     pub fn new(usb_bus: &'a UsbBusAllocator<B>) -> UsbManager<'a, B> {
         let serial_port = SerialPort::new(usb_bus);
-        let keyboard = HIDClass::new(usb_bus, KeyboardReport::desc(), 20);
+        //NKRO bitmap, on an interface of its own as usbd-hid only lets a boot interface send
+        //while the host has asked for the boot protocol
+        let keyboard = HIDClass::new(usb_bus, &keyboard::report::NKRO_DESCRIPTOR, 20);
+        //6KRO keyboard for hosts that ask for the boot protocol, as a BIOS does
+        let boot_keyboard = HIDClass::new_with_settings(
+            usb_bus,
+            KeyboardReport::desc(),
+            20,
+            HidClassSettings {
+                subclass: HidSubClass::Boot,
+                protocol: HidProtocol::Keyboard,
+                config: ProtocolModeConfig::DefaultBehavior,
+                locale: HidCountryCode::NotSupported,
+            },
+        );
+        //consumer control page (0x0C) for media keys, which hosts ignore on the keyboard page
+        let consumer = HIDClass::new(usb_bus, MediaKeyboardReport::desc(), 20);
+        //generic desktop system control for power down, sleep and wake up
+        let system = HIDClass::new(usb_bus, &keyboard::system::SYSTEM_CONTROL_DESCRIPTOR, 20);
+        //polled every ms so mouse keys move the pointer smoothly
+        let mouse = HIDClass::new(usb_bus, MouseReport::desc(), 1);
 
         // Create a USB device with a fake VID and PID
         let usb_device = UsbDeviceBuilder::new(usb_bus, UsbVidPid(0x16c0, 0x27dd))
@@ -33,12 +62,37 @@ where
         UsbManager {
             serial_port,
             keyboard,
+            boot_keyboard,
+            consumer,
+            system,
+            mouse,
             usb_device,
         }
     }
 
-    pub fn keyboard_borrow_mut(&mut self) -> &mut HIDClass<'a, B> {
-        &mut self.keyboard
+    /// Send the 6KRO boot report while the host has asked for the boot protocol, otherwise the
+    /// NKRO bitmap
+    pub fn push_keyboard_report(&mut self, boot: &[u8], nkro: &[u8]) -> usb_device::Result<usize> {
+        if matches!(
+            self.boot_keyboard.get_protocol_mode(),
+            Ok(HidProtocolMode::Boot)
+        ) {
+            self.boot_keyboard.push_raw_input(boot)
+        } else {
+            self.keyboard.push_raw_input(nkro)
+        }
+    }
+
+    pub fn consumer_borrow_mut(&mut self) -> &mut HIDClass<'a, B> {
+        &mut self.consumer
+    }
+
+    pub fn system_borrow_mut(&mut self) -> &mut HIDClass<'a, B> {
+        &mut self.system
+    }
+
+    pub fn mouse_borrow_mut(&mut self) -> &mut HIDClass<'a, B> {
+        &mut self.mouse
     }
 
     pub fn serial_port_borrow_mut(&mut self) -> &mut SerialPort<'a, B> {
@@ -47,10 +101,14 @@ where
 
     pub fn service_irq(&mut self) {
         // Poll the USB driver with all of our supported USB Classes
-        if self
-            .usb_device
-            .poll(&mut [&mut self.serial_port, &mut self.keyboard])
-        {
+        if self.usb_device.poll(&mut [
+            &mut self.serial_port,
+            &mut self.keyboard,
+            &mut self.boot_keyboard,
+            &mut self.consumer,
+            &mut self.system,
+            &mut self.mouse,
+        ]) {
             let mut buf = [0u8; 64];
             match self.serial_port.read(&mut buf) {
                 Err(_e) => {}
@@ -62,6 +120,10 @@ where
                 Err(_e) => {}
                 Ok(_count) => {}
             }
+            match self.boot_keyboard.pull_raw_output(&mut buf) {
+                Err(_e) => {}
+                Ok(_count) => {}
+            }
         }
     }
 }
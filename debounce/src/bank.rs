@@ -0,0 +1,55 @@
+/// Debounces every bit of a port word at once using vertical counters
+///
+/// Each bit behaves like a pin using `Algorithm::ShiftRegister { samples: SAMPLES }`, with the
+/// count of reads disagreeing with the debounced state held across three bit planes.
+pub struct BankDebouncer<const SAMPLES: u8> {
+    state: u32,
+    count: [u32; 3],
+}
+
+impl<const SAMPLES: u8> BankDebouncer<SAMPLES> {
+    pub const MAX_SAMPLES: u8 = 8;
+
+    pub fn new(initial: u32) -> BankDebouncer<SAMPLES> {
+        assert!(
+            (1..=Self::MAX_SAMPLES).contains(&SAMPLES),
+            "bank debounce window must be 1..=8 samples"
+        );
+
+        BankDebouncer {
+            state: initial,
+            count: [0; 3],
+        }
+    }
+
+    /// Sample the whole port, returning a mask of the bits whose debounced state changed
+    pub fn update(&mut self, sample: u32) -> u32 {
+        let diff = sample ^ self.state;
+
+        //bits that have already disagreed for SAMPLES - 1 reads
+        let mut changed = diff;
+        for (bit, plane) in self.count.iter().enumerate() {
+            changed &= if (SAMPLES - 1) >> bit & 1 == 1 {
+                *plane
+            } else {
+                !*plane
+            };
+        }
+
+        //count up where the read disagrees, reset where it agrees or has just changed
+        let keep = diff & !changed;
+        let mut carry = diff;
+        for plane in &mut self.count {
+            let next = *plane ^ carry;
+            carry &= *plane;
+            *plane = next & keep;
+        }
+
+        self.state ^= changed;
+        changed
+    }
+
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+}
@@ -0,0 +1,141 @@
+//! embedded-hal 1.0 versions of the debounced pin
+
+use crate::{DebouncerState, ReadPin};
+use embedded_hal_1::digital::{ErrorType, InputPin};
+
+/// Pins read through embedded-hal 1.0
+pub enum Hal1 {}
+
+impl<P> ReadPin<Hal1> for P
+where
+    P: InputPin,
+{
+    type Error = P::Error;
+
+    fn read(&mut self) -> Result<bool, P::Error> {
+        self.is_high()
+    }
+}
+
+pub type DebouncedPin<P> = crate::DebouncedPin<P, Hal1>;
+
+impl<P> ErrorType for DebouncedPin<P>
+where
+    P: ErrorType,
+{
+    type Error = P::Error;
+}
+
+impl<P> InputPin for DebouncedPin<P>
+where
+    P: InputPin,
+{
+    //neither high nor low until settled
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.state() == DebouncerState::High)
+    }
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.state() == DebouncerState::Low)
+    }
+}
+
+#[cfg(feature = "async")]
+pub use self::asynch::AsyncDebouncedPin;
+
+#[cfg(feature = "async")]
+mod asynch {
+    use super::DebouncedPin;
+    use crate::{DebouncerState, Edge, Event};
+    use embedded_hal_1::digital::{ErrorType, InputPin};
+    use embedded_hal_async::delay::DelayNs;
+    use embedded_hal_async::digital::Wait;
+
+    /// Samples a pin every `period_us` while waiting, so waits only resolve on debounced levels and edges
+    pub struct AsyncDebouncedPin<P, D> {
+        pin: DebouncedPin<P>,
+        delay: D,
+        period_us: u32,
+    }
+
+    impl<P, D> AsyncDebouncedPin<P, D>
+    where
+        P: InputPin,
+        D: DelayNs,
+    {
+        pub fn new(pin: DebouncedPin<P>, delay: D, period_us: u32) -> AsyncDebouncedPin<P, D> {
+            AsyncDebouncedPin {
+                pin,
+                delay,
+                period_us,
+            }
+        }
+
+        pub fn pin_borrow(&self) -> &DebouncedPin<P> {
+            &self.pin
+        }
+
+        async fn next_event(&mut self) -> Result<Event, P::Error> {
+            self.delay.delay_us(self.period_us).await;
+            self.pin.update()
+        }
+
+        async fn wait_for_state(&mut self, state: DebouncerState) -> Result<(), P::Error> {
+            while self.pin.state() != state {
+                self.next_event().await?;
+            }
+            Ok(())
+        }
+
+        async fn wait_for_edge(&mut self, edge: Option<Edge>) -> Result<(), P::Error> {
+            loop {
+                let event = self.next_event().await?;
+                match edge {
+                    Some(edge) if event.edge == edge => return Ok(()),
+                    None if event.edge != Edge::None => return Ok(()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    impl<P, D> ErrorType for AsyncDebouncedPin<P, D>
+    where
+        P: ErrorType,
+    {
+        type Error = P::Error;
+    }
+
+    impl<P, D> InputPin for AsyncDebouncedPin<P, D>
+    where
+        P: InputPin,
+    {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            self.pin.is_high()
+        }
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.pin.is_low()
+        }
+    }
+
+    impl<P, D> Wait for AsyncDebouncedPin<P, D>
+    where
+        P: InputPin,
+        D: DelayNs,
+    {
+        async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+            self.wait_for_state(DebouncerState::High).await
+        }
+        async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+            self.wait_for_state(DebouncerState::Low).await
+        }
+        async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+            self.wait_for_edge(Some(Edge::Rising)).await
+        }
+        async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+            self.wait_for_edge(Some(Edge::Falling)).await
+        }
+        async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+            self.wait_for_edge(None).await
+        }
+    }
+}
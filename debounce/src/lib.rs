@@ -1,41 +1,392 @@
 #![no_std]
 
+mod bank;
+#[cfg(feature = "embedded-hal-1")]
+pub mod hal1;
+#[cfg(feature = "stats")]
+mod stats;
+mod value;
+
+pub use bank::BankDebouncer;
+#[cfg(feature = "stats")]
+pub use stats::Stats;
+pub use value::{Debounce, Window};
+
+use core::marker::PhantomData;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum DebouncerState {
     Low,
     High,
     Unknown,
 }
 
-pub struct DebouncedPin<P> {
-    pin: P,
+impl From<bool> for DebouncerState {
+    fn from(high: bool) -> Self {
+        if high {
+            DebouncerState::High
+        } else {
+            DebouncerState::Low
+        }
+    }
+}
+
+/// Change in debounced level reported by an update
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Edge {
+    None,
+    Rising,
+    Falling,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Event {
+    pub edge: Edge,
+    /// Timestamp passed to `update_at`, or the scan count when driven by `update`
+    pub at: u32,
+}
+
+/// Filter used to decide when a sampled level has settled
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Algorithm {
+    /// Change state once the last `samples` reads all agree
+    ShiftRegister { samples: u8 },
+    /// Count up on high reads and down on low reads, changing state when the count reaches either end
+    Integrator { samples: u8 },
+    /// Report the first edge straight away, then hold it until the read has been stable for `samples` reads
+    Eager { samples: u8 },
+    /// Change state once the read has been stable for `stable` ticks of the update timestamp
+    Timed { stable: u32 },
+    /// Report the first edge straight away, then hold it until the read has been stable for `stable` ticks
+    TimedEager { stable: u32 },
+}
+
+impl Algorithm {
+    fn validate(self) {
+        if let Some(samples) = self.samples() {
+            assert!(samples > 0, "debounce window must be at least 1 sample");
+        }
+    }
+
+    fn window(self) -> Window {
+        match self {
+            Algorithm::ShiftRegister { samples }
+            | Algorithm::Integrator { samples }
+            | Algorithm::Eager { samples } => Window::Samples(samples as u32),
+            Algorithm::Timed { stable } | Algorithm::TimedEager { stable } => {
+                Window::Duration(stable)
+            }
+        }
+    }
+
+    fn samples(self) -> Option<u8> {
+        match self {
+            Algorithm::ShiftRegister { samples } => Some(samples),
+            Algorithm::Integrator { samples } => Some(samples),
+            Algorithm::Eager { samples } => Some(samples),
+            Algorithm::Timed { .. } => None,
+            Algorithm::TimedEager { .. } => None,
+        }
+    }
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::ShiftRegister { samples: 5 }
+    }
+}
+
+enum Filter {
+    //waiting for the first full window of agreeing reads
+    Settling {
+        algorithm: Algorithm,
+        debounce: Debounce<bool>,
+    },
+    //shift register and timed algorithms
+    Debounce(Debounce<bool>),
+    Integrator {
+        max: u8,
+        count: u8,
+    },
+    Eager {
+        window: u8,
+        hold: u8,
+        previous: bool,
+    },
+    TimedEager {
+        stable: u32,
+        locked: bool,
+        previous: bool,
+        since: u32,
+    },
+}
+
+impl Filter {
+    fn new(algorithm: Algorithm, state: bool) -> Filter {
+        match algorithm {
+            Algorithm::ShiftRegister { .. } | Algorithm::Timed { .. } => {
+                Filter::Debounce(Debounce::new(state, algorithm.window()))
+            }
+            Algorithm::Integrator { samples } => Filter::Integrator {
+                max: samples,
+                count: if state { samples } else { 0 },
+            },
+            Algorithm::Eager { samples } => Filter::Eager {
+                window: samples,
+                hold: 0,
+                previous: state,
+            },
+            Algorithm::TimedEager { stable } => Filter::TimedEager {
+                stable,
+                locked: false,
+                previous: state,
+                since: 0,
+            },
+        }
+    }
+
+    fn settling(algorithm: Algorithm) -> Filter {
+        Filter::Settling {
+            algorithm,
+            debounce: Debounce::unknown(algorithm.window()),
+        }
+    }
+
+    fn update(&mut self, sample: bool, last: bool, now: u32) -> bool {
+        match self {
+            Filter::Settling {
+                algorithm,
+                debounce,
+            } => match debounce.update_at(sample, now) {
+                Some(state) => {
+                    *self = Filter::new(*algorithm, state);
+                    state
+                }
+                None => last,
+            },
+            Filter::Debounce(debounce) => {
+                debounce.update_at(sample, now);
+                debounce.value().unwrap_or(last)
+            }
+            Filter::Integrator { max, count } => {
+                if sample {
+                    *count = count.saturating_add(1).min(*max);
+                } else {
+                    *count = count.saturating_sub(1);
+                }
+
+                match *count {
+                    0 => false,
+                    c if c == *max => true,
+                    _ => last,
+                }
+            }
+            Filter::Eager {
+                window,
+                hold,
+                previous,
+            } => {
+                if *hold > 0 {
+                    //locked out until the read has been stable for the whole window
+                    *hold = if sample == *previous {
+                        *hold - 1
+                    } else {
+                        *window - 1
+                    };
+                }
+                *previous = sample;
+
+                if *hold == 0 && sample != last {
+                    *hold = *window - 1;
+                    sample
+                } else {
+                    last
+                }
+            }
+            Filter::TimedEager {
+                stable,
+                locked,
+                previous,
+                since,
+            } => {
+                if *locked && now.wrapping_sub(*since) >= *stable {
+                    *locked = false;
+                }
+                if sample != *previous {
+                    *previous = sample;
+                    *since = now;
+                }
+
+                if !*locked && sample != last {
+                    //hold the new state until the line has settled from now
+                    *locked = true;
+                    *since = now;
+                    sample
+                } else {
+                    last
+                }
+            }
+        }
+    }
+}
+
+/// Debounces a sampled level, independent of how the level is read
+pub struct Debouncer {
     last: bool,
-    history: u8,
+    filter: Filter,
+    //timestamp of the latest update, counts scans when driven by `update`
+    now: u32,
+    #[cfg(feature = "stats")]
+    stats: Stats,
 }
 
-impl<P, E> DebouncedPin<P>
+impl Debouncer {
+    /// Start in `initial`, or with `DebouncerState::Unknown` wait for the first full window of agreeing reads
+    pub fn new(initial: impl Into<DebouncerState>, algorithm: Algorithm) -> Debouncer {
+        algorithm.validate();
+
+        let (last, filter) = match initial.into() {
+            DebouncerState::High => (true, Filter::new(algorithm, true)),
+            DebouncerState::Low => (false, Filter::new(algorithm, false)),
+            DebouncerState::Unknown => (false, Filter::settling(algorithm)),
+        };
+
+        Debouncer {
+            last,
+            filter,
+            now: 0,
+            #[cfg(feature = "stats")]
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn state(&self) -> DebouncerState {
+        match self.filter {
+            Filter::Settling { .. } => DebouncerState::Unknown,
+            _ => self.last.into(),
+        }
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.state() != DebouncerState::Unknown
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /// Add a read, treating each call as one tick
+    pub fn update(&mut self, sample: bool) -> Event {
+        self.update_at(sample, self.next_tick())
+    }
+
+    /// Add a read taken at a timestamp from a free running, wrapping clock
+    pub fn update_at(&mut self, sample: bool, now: u32) -> Event {
+        let previous = self.state();
+        self.now = now;
+        self.last = self.filter.update(sample, self.last, now);
+
+        //settling from unknown is not an edge
+        let edge = match (previous, self.state()) {
+            (DebouncerState::Low, DebouncerState::High) => Edge::Rising,
+            (DebouncerState::High, DebouncerState::Low) => Edge::Falling,
+            _ => Edge::None,
+        };
+
+        #[cfg(feature = "stats")]
+        self.stats.record(sample, edge);
+
+        Event { edge, at: now }
+    }
+
+    fn next_tick(&self) -> u32 {
+        self.now.wrapping_add(1)
+    }
+}
+
+/// Reads a pin's level through the embedded-hal version `H`
+pub trait ReadPin<H> {
+    type Error;
+
+    fn read(&mut self) -> Result<bool, Self::Error>;
+}
+
+/// Pins read through embedded-hal 0.2, see `hal1::Hal1` for embedded-hal 1.0
+pub enum Hal02 {}
+
+impl<P> ReadPin<Hal02> for P
 where
-    P: embedded_hal::digital::v2::InputPin<Error = E>,
+    P: embedded_hal::digital::v2::InputPin,
 {
-    pub fn new(pin: P, default_state: bool) -> DebouncedPin<P> {
+    type Error = P::Error;
+
+    fn read(&mut self) -> Result<bool, P::Error> {
+        self.is_high()
+    }
+}
+
+/// Debounced input pin, read through embedded-hal 0.2 unless `H` says otherwise, see `hal1`
+pub struct DebouncedPin<P, H = Hal02> {
+    pin: P,
+    debouncer: Debouncer,
+    hal: PhantomData<H>,
+}
+
+impl<P, H> DebouncedPin<P, H> {
+    pub fn state(&self) -> DebouncerState {
+        self.debouncer.state()
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.debouncer.is_settled()
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> &Stats {
+        self.debouncer.stats()
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn reset_stats(&mut self) {
+        self.debouncer.reset_stats()
+    }
+}
+
+impl<P, H> DebouncedPin<P, H>
+where
+    P: ReadPin<H>,
+{
+    pub fn new(pin: P, default_state: bool) -> DebouncedPin<P, H> {
+        Self::with_algorithm(pin, default_state, Algorithm::default())
+    }
+
+    /// Start in `initial`, or with `DebouncerState::Unknown` wait for the first full window of agreeing reads
+    pub fn with_algorithm(
+        pin: P,
+        initial: impl Into<DebouncerState>,
+        algorithm: Algorithm,
+    ) -> DebouncedPin<P, H> {
         DebouncedPin {
             pin,
-            last: default_state,
-            history: if default_state { u8::MAX } else { 0 },
+            debouncer: Debouncer::new(initial, algorithm),
+            hal: PhantomData,
         }
     }
 
-    pub fn update(&mut self) -> Result<(), E> {
-        const MASK: u8 = 0b11100000; //look for 5 stable values
-
-        self.history = (self.history << 1) | if self.pin.is_high()? { 1 } else { 0 } | MASK;
-
-        self.last = match self.history {
-            u8::MAX => true,
-            MASK => false,
-            _ => self.last,
-        };
+    /// Sample the pin, treating each call as one tick
+    pub fn update(&mut self) -> Result<Event, P::Error> {
+        self.update_at(self.debouncer.next_tick())
+    }
 
-        Ok(())
+    /// Sample the pin at a timestamp from a free running, wrapping clock
+    pub fn update_at(&mut self, now: u32) -> Result<Event, P::Error> {
+        Ok(self.debouncer.update_at(self.pin.read()?, now))
     }
 }
 
@@ -45,13 +396,18 @@ where
 {
     type Error = P::Error;
 
+    //neither high nor low until settled
     fn is_high(&self) -> Result<bool, Self::Error> {
-        Ok(self.last)
+        Ok(self.state() == DebouncerState::High)
     }
     fn is_low(&self) -> Result<bool, Self::Error> {
-        Ok(!self.last)
+        Ok(self.state() == DebouncerState::Low)
     }
 }
 
 #[cfg(test)]
+mod proptests;
+#[cfg(test)]
+//the derive from failure implements its traits inside a const
+#[allow(non_local_definitions)]
 mod tests;
@@ -0,0 +1,266 @@
+//! Invariants every debounce mode has to hold on random bounce traces
+
+extern crate std;
+
+use super::{Algorithm, BankDebouncer, Debounce, Debouncer, DebouncerState, Edge, Window};
+use proptest::prelude::*;
+use std::vec::Vec;
+
+//runs of 1..=12 identical reads, so traces mix bounces with levels long enough to settle
+fn trace() -> impl Strategy<Value = Vec<bool>> {
+    prop::collection::vec((any::<bool>(), 1..=12usize), 0..64).prop_map(|runs| {
+        runs.into_iter()
+            .flat_map(|(level, len)| core::iter::repeat_n(level, len))
+            .collect()
+    })
+}
+
+//reads with 0..=5 ticks between them
+fn timed_trace() -> impl Strategy<Value = Vec<(bool, u32)>> {
+    (trace(), prop::collection::vec(0..=5u32, 768)).prop_map(|(reads, gaps)| {
+        let mut now = 0;
+        reads
+            .into_iter()
+            .zip(gaps)
+            .map(|(read, gap)| {
+                now += gap;
+                (read, now)
+            })
+            .collect()
+    })
+}
+
+fn counted_algorithm() -> impl Strategy<Value = Algorithm> {
+    (0..3, 1..=8u8).prop_map(|(kind, samples)| match kind {
+        0 => Algorithm::ShiftRegister { samples },
+        1 => Algorithm::Integrator { samples },
+        _ => Algorithm::Eager { samples },
+    })
+}
+
+fn timed_algorithm() -> impl Strategy<Value = Algorithm> {
+    (any::<bool>(), 0..=20u32).prop_map(|(eager, stable)| {
+        if eager {
+            Algorithm::TimedEager { stable }
+        } else {
+            Algorithm::Timed { stable }
+        }
+    })
+}
+
+fn high(debouncer: &Debouncer) -> bool {
+    debouncer.state() == DebouncerState::High
+}
+
+proptest! {
+    #[test]
+    fn counted_modes(algorithm in counted_algorithm(), initial in any::<bool>(), reads in trace()) {
+        let samples = match algorithm {
+            Algorithm::ShiftRegister { samples }
+            | Algorithm::Integrator { samples }
+            | Algorithm::Eager { samples } => samples as usize,
+            _ => unreachable!(),
+        };
+        let eager = matches!(algorithm, Algorithm::Eager { .. });
+
+        let mut debouncer = Debouncer::new(initial, algorithm);
+        let mut last_change: Option<usize> = None;
+        let mut run = 0;
+        //reads of each level since the last change, for the integrator
+        let mut seen = [0usize; 2];
+
+        for (i, &read) in reads.iter().enumerate() {
+            let previous_run = run;
+            run = if i > 0 && reads[i - 1] == read { run + 1 } else { 1 };
+            seen[read as usize] += 1;
+
+            let before = high(&debouncer);
+            let event = debouncer.update(read);
+            let after = high(&debouncer);
+
+            prop_assert_eq!(
+                event.edge,
+                match (before, after) {
+                    (false, true) => Edge::Rising,
+                    (true, false) => Edge::Falling,
+                    _ => Edge::None,
+                }
+            );
+
+            if before != after {
+                //only ever moves to the current read
+                prop_assert_eq!(after, read);
+
+                //never changes on a run shorter than the window
+                match algorithm {
+                    Algorithm::ShiftRegister { .. } => prop_assert!(run >= samples),
+                    Algorithm::Integrator { .. } => prop_assert!(seen[read as usize] >= samples),
+                    _ => {}
+                }
+
+                //never changes twice within one window
+                if let Some(last_change) = last_change {
+                    prop_assert!(i - last_change >= samples);
+                }
+                last_change = Some(i);
+                seen = [0; 2];
+            }
+
+            //always follows a stable input once the window has passed
+            if run >= samples {
+                prop_assert_eq!(after, read);
+            }
+
+            //eager reports the first edge straight away once the line has been stable for a
+            //window since the previous change
+            if eager
+                && read != before
+                && last_change.is_none_or(|c| i - c >= samples && previous_run >= samples)
+            {
+                prop_assert_eq!(after, read);
+            }
+        }
+    }
+
+    #[test]
+    fn timed_modes(algorithm in timed_algorithm(), initial in any::<bool>(), reads in timed_trace()) {
+        let (stable, eager) = match algorithm {
+            Algorithm::Timed { stable } => (stable, false),
+            Algorithm::TimedEager { stable } => (stable, true),
+            _ => unreachable!(),
+        };
+
+        let mut debouncer = Debouncer::new(initial, algorithm);
+        let mut last_change: Option<u32> = None;
+        //timestamp of the first read of the current run
+        let mut since = 0;
+
+        for (i, &(read, now)) in reads.iter().enumerate() {
+            if i == 0 || reads[i - 1].0 != read {
+                since = now;
+            }
+            //the first run counts from the initial state
+            if i == 0 && read == initial {
+                since = 0;
+            }
+
+            let before = high(&debouncer);
+            debouncer.update_at(read, now);
+            let after = high(&debouncer);
+
+            if before != after {
+                prop_assert_eq!(after, read);
+
+                if !eager {
+                    prop_assert!(now - since >= stable);
+                }
+
+                if let Some(last_change) = last_change {
+                    prop_assert!(now - last_change >= stable);
+                }
+                last_change = Some(now);
+            }
+
+            if now - since >= stable && !(eager && last_change.is_some_and(|c| now - c < stable)) {
+                prop_assert_eq!(after, read);
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_start(algorithm in counted_algorithm(), reads in trace()) {
+        let samples = match algorithm {
+            Algorithm::ShiftRegister { samples }
+            | Algorithm::Integrator { samples }
+            | Algorithm::Eager { samples } => samples as usize,
+            _ => unreachable!(),
+        };
+
+        let mut debouncer = Debouncer::new(DebouncerState::Unknown, algorithm);
+        let mut run = 0;
+        for (i, &read) in reads.iter().enumerate() {
+            run = if i > 0 && reads[i - 1] == read { run + 1 } else { 1 };
+
+            let settled = debouncer.is_settled();
+            let event = debouncer.update(read);
+
+            if !settled {
+                //settling is not an edge, and needs a full window
+                prop_assert_eq!(event.edge, Edge::None);
+                prop_assert_eq!(debouncer.is_settled(), run >= samples);
+                if debouncer.is_settled() {
+                    prop_assert_eq!(high(&debouncer), read);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generic_values(samples in 1..=8u32, reads in prop::collection::vec((0..3u8, 1..=12usize), 0..64)) {
+        let reads = reads
+            .into_iter()
+            .flat_map(|(value, len)| core::iter::repeat_n(value, len))
+            .collect::<Vec<_>>();
+
+        let mut debounce = Debounce::new(0u8, Window::Samples(samples));
+        let mut last_change: Option<usize> = None;
+        let mut run = 0;
+        for (i, &read) in reads.iter().enumerate() {
+            run = if i > 0 && reads[i - 1] == read { run + 1 } else { 1 };
+
+            let before = debounce.value();
+            let committed = debounce.update(read);
+
+            if let Some(value) = committed {
+                prop_assert_ne!(before, Some(value));
+                prop_assert_eq!(value, read);
+                prop_assert!(run >= samples as usize);
+                if let Some(last_change) = last_change {
+                    prop_assert!(i - last_change >= samples as usize);
+                }
+                last_change = Some(i);
+            } else {
+                prop_assert_eq!(before, debounce.value());
+            }
+
+            if run >= samples as usize {
+                prop_assert_eq!(debounce.value(), Some(read));
+            }
+        }
+    }
+
+    #[test]
+    fn bank_bits(initial in any::<u32>(), words in prop::collection::vec((any::<u32>(), 1..=12usize), 0..64)) {
+        let reads = words
+            .into_iter()
+            .flat_map(|(word, len)| core::iter::repeat_n(word, len))
+            .collect::<Vec<_>>();
+
+        let mut bank = BankDebouncer::<4>::new(initial);
+        let mut run = [0usize; 32];
+        let mut last_change = [None::<usize>; 32];
+        for (i, &word) in reads.iter().enumerate() {
+            let before = bank.state();
+            let changed = bank.update(word);
+            prop_assert_eq!(changed, before ^ bank.state());
+
+            for bit in 0..32 {
+                let read = word >> bit & 1;
+                run[bit] = if i > 0 && reads[i - 1] >> bit & 1 == read { run[bit] + 1 } else { 1 };
+
+                if changed >> bit & 1 == 1 {
+                    prop_assert_eq!(bank.state() >> bit & 1, read);
+                    prop_assert!(run[bit] >= 4);
+                    if let Some(last_change) = last_change[bit] {
+                        prop_assert!(i - last_change >= 4);
+                    }
+                    last_change[bit] = Some(i);
+                }
+
+                if run[bit] >= 4 {
+                    prop_assert_eq!(bank.state() >> bit & 1, read);
+                }
+            }
+        }
+    }
+}
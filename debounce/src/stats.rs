@@ -0,0 +1,35 @@
+use crate::Edge;
+
+/// Switch health counters, a rising bounce count points at a worn or dirty switch
+///
+/// Bounces are tallied when the debounced state next changes, as only then is it known which
+/// read change was kept. A bounce is a change in the read and back, so two read changes.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct Stats {
+    /// Changes in the read and back that did not change the debounced state
+    pub bounces: u32,
+    /// Debounced state changes
+    pub transitions: u32,
+    /// Most bounces seen between two debounced state changes
+    pub longest_burst: u32,
+    changes: u32,
+    previous: Option<bool>,
+}
+
+impl Stats {
+    pub(crate) fn record(&mut self, sample: bool, edge: Edge) {
+        if self.previous.is_some_and(|p| p != sample) {
+            self.changes = self.changes.saturating_add(1);
+        }
+        self.previous = Some(sample);
+
+        if edge != Edge::None {
+            //the latest read change is the one that was kept, the others come in pairs
+            let burst = self.changes.saturating_sub(1) / 2;
+            self.bounces = self.bounces.saturating_add(burst);
+            self.longest_burst = self.longest_burst.max(burst);
+            self.transitions = self.transitions.saturating_add(1);
+            self.changes = 0;
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use super::DebouncedPin;
+use super::{
+    Algorithm, BankDebouncer, Debounce, DebouncedPin, DebouncerState, Edge, Event, Window,
+};
 use embedded_hal::digital::v2::InputPin;
 use failure::Fail;
 
@@ -126,3 +128,758 @@ fn change_low_after_5_consecutive_afterbouncing_reads() {
     debouncer.update().unwrap();
     assert!(debouncer.is_low().unwrap());
 }
+
+//widest sample window tried by the tests that step through every window
+const WINDOWS: u8 = 32;
+
+fn algorithms(samples: u8) -> [Algorithm; 2] {
+    [
+        Algorithm::ShiftRegister { samples },
+        Algorithm::Integrator { samples },
+    ]
+}
+
+fn reads_until_high(debouncer: &mut DebouncedPin<TestInputPin>) -> usize {
+    debouncer.pin.set_value(true);
+    let mut reads = 0;
+    while debouncer.is_low().unwrap() {
+        debouncer.update().unwrap();
+        reads += 1;
+    }
+    reads
+}
+
+#[test]
+fn default_algorithm_is_5_sample_shift_register() {
+    assert_eq!(
+        Algorithm::default(),
+        Algorithm::ShiftRegister { samples: 5 }
+    );
+}
+
+#[test]
+fn change_after_window_consecutive_reads_high() {
+    for samples in 1..=WINDOWS {
+        for algorithm in algorithms(samples) {
+            let test_pin = TestInputPin::new(true);
+            let mut debouncer = DebouncedPin::with_algorithm(test_pin, false, algorithm);
+            for _ in 1..samples {
+                debouncer.update().unwrap();
+                assert!(debouncer.is_low().unwrap(), "{:?}", algorithm);
+            }
+
+            debouncer.update().unwrap();
+            assert!(debouncer.is_high().unwrap(), "{:?}", algorithm);
+        }
+    }
+}
+
+#[test]
+fn change_after_window_consecutive_reads_low() {
+    for samples in 1..=WINDOWS {
+        for algorithm in algorithms(samples) {
+            let test_pin = TestInputPin::new(false);
+            let mut debouncer = DebouncedPin::with_algorithm(test_pin, true, algorithm);
+            for _ in 1..samples {
+                debouncer.update().unwrap();
+                assert!(debouncer.is_high().unwrap(), "{:?}", algorithm);
+            }
+
+            debouncer.update().unwrap();
+            assert!(debouncer.is_low().unwrap(), "{:?}", algorithm);
+        }
+    }
+}
+
+#[test]
+fn shift_register_ignores_bounces_shorter_than_window() {
+    for samples in 2..=WINDOWS {
+        let test_pin = TestInputPin::new(false);
+        let mut debouncer =
+            DebouncedPin::with_algorithm(test_pin, false, Algorithm::ShiftRegister { samples });
+        for run in 1..samples {
+            debouncer.pin.set_value(true);
+            for _ in 0..run {
+                debouncer.update().unwrap();
+                assert!(debouncer.is_low().unwrap());
+            }
+            debouncer.pin.set_value(false);
+            debouncer.update().unwrap();
+            assert!(debouncer.is_low().unwrap());
+        }
+    }
+}
+
+#[test]
+fn integrator_needs_net_window_of_high_reads() {
+    for samples in 2..=WINDOWS {
+        let test_pin = TestInputPin::new(false);
+        let mut debouncer =
+            DebouncedPin::with_algorithm(test_pin, false, Algorithm::Integrator { samples });
+
+        //alternating reads never make progress
+        for i in 0..(samples as usize * 4) {
+            debouncer.pin.set_value(i % 2 == 0);
+            debouncer.update().unwrap();
+            assert!(debouncer.is_low().unwrap());
+        }
+
+        //two highs for every low gain one step per three reads
+        for i in 0..(samples as usize * 3 - 1) {
+            debouncer.pin.set_value(i % 3 != 2);
+            debouncer.update().unwrap();
+        }
+        assert!(debouncer.is_high().unwrap());
+    }
+}
+
+#[test]
+#[should_panic]
+fn rejects_empty_window() {
+    DebouncedPin::with_algorithm(
+        TestInputPin::new(false),
+        false,
+        Algorithm::ShiftRegister { samples: 0 },
+    );
+}
+
+#[test]
+fn widest_window() {
+    for algorithm in algorithms(u8::MAX) {
+        let test_pin = TestInputPin::new(true);
+        let mut debouncer = DebouncedPin::with_algorithm(test_pin, false, algorithm);
+        for _ in 1..u8::MAX {
+            debouncer.update().unwrap();
+            assert!(debouncer.is_low().unwrap(), "{:?}", algorithm);
+        }
+        debouncer.update().unwrap();
+        assert!(debouncer.is_high().unwrap(), "{:?}", algorithm);
+
+        //held high past the window
+        debouncer.update().unwrap();
+        assert!(debouncer.is_high().unwrap(), "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn eager_changes_on_first_read() {
+    for samples in 1..=WINDOWS {
+        let algorithm = Algorithm::Eager { samples };
+        let mut debouncer = DebouncedPin::with_algorithm(TestInputPin::new(true), false, algorithm);
+        debouncer.update().unwrap();
+        assert!(debouncer.is_high().unwrap());
+
+        let mut debouncer = DebouncedPin::with_algorithm(TestInputPin::new(false), true, algorithm);
+        debouncer.update().unwrap();
+        assert!(debouncer.is_low().unwrap());
+    }
+}
+
+#[test]
+fn eager_ignores_bounces_until_stable_for_window() {
+    for samples in 2..=WINDOWS {
+        let test_pin = TestInputPin::new(true);
+        let mut debouncer =
+            DebouncedPin::with_algorithm(test_pin, false, Algorithm::Eager { samples });
+        debouncer.update().unwrap();
+        assert!(debouncer.is_high().unwrap());
+
+        //contact bounce after the press is held off
+        for i in 0..15 {
+            debouncer.pin.set_value(i % 2 == 1);
+            debouncer.update().unwrap();
+            assert!(debouncer.is_high().unwrap());
+        }
+
+        //window - 1 stable reads are not enough to release the lock
+        debouncer.pin.set_value(true);
+        for _ in 1..samples {
+            debouncer.update().unwrap();
+        }
+        debouncer.pin.set_value(false);
+        debouncer.update().unwrap();
+        assert!(debouncer.is_high().unwrap());
+
+        debouncer.pin.set_value(true);
+        for _ in 0..samples {
+            debouncer.update().unwrap();
+        }
+        debouncer.pin.set_value(false);
+        debouncer.update().unwrap();
+        assert!(debouncer.is_low().unwrap());
+    }
+}
+
+#[test]
+fn eager_has_lower_latency_than_shift_register() {
+    let mut eager = DebouncedPin::with_algorithm(
+        TestInputPin::new(false),
+        false,
+        Algorithm::Eager { samples: 5 },
+    );
+    let mut shift_register = DebouncedPin::new(TestInputPin::new(false), false);
+
+    assert_eq!(reads_until_high(&mut eager), 1);
+    assert_eq!(reads_until_high(&mut shift_register), 5);
+}
+
+#[test]
+fn eager_reports_glitches_that_shift_register_rejects() {
+    let mut eager = DebouncedPin::with_algorithm(
+        TestInputPin::new(false),
+        false,
+        Algorithm::Eager { samples: 5 },
+    );
+    let mut shift_register = DebouncedPin::new(TestInputPin::new(false), false);
+
+    //single read glitch
+    for debouncer in [&mut eager, &mut shift_register] {
+        debouncer.pin.set_value(true);
+        debouncer.update().unwrap();
+        debouncer.pin.set_value(false);
+    }
+    assert!(eager.is_high().unwrap());
+    assert!(shift_register.is_low().unwrap());
+
+    //eager holds the glitch for a full window before following the line again
+    for _ in 0..4 {
+        eager.update().unwrap();
+        shift_register.update().unwrap();
+        assert!(eager.is_high().unwrap());
+        assert!(shift_register.is_low().unwrap());
+    }
+    eager.update().unwrap();
+    assert!(eager.is_low().unwrap());
+}
+
+fn update_at(debouncer: &mut DebouncedPin<TestInputPin>, value: bool, now: u32) -> bool {
+    debouncer.pin.set_value(value);
+    debouncer.update_at(now).unwrap();
+    debouncer.is_high().unwrap()
+}
+
+#[test]
+fn timed_changes_after_stable_duration() {
+    let test_pin = TestInputPin::new(false);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::Timed { stable: 5_000 });
+
+    assert!(!update_at(&mut debouncer, true, 1_000));
+    assert!(!update_at(&mut debouncer, true, 5_999));
+    assert!(update_at(&mut debouncer, true, 6_000));
+
+    assert!(update_at(&mut debouncer, false, 7_000));
+    assert!(update_at(&mut debouncer, false, 11_999));
+    assert!(!update_at(&mut debouncer, false, 12_000));
+}
+
+#[test]
+fn timed_restarts_on_bounce() {
+    let test_pin = TestInputPin::new(false);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::Timed { stable: 5_000 });
+
+    assert!(!update_at(&mut debouncer, true, 0));
+    assert!(!update_at(&mut debouncer, false, 4_000));
+    assert!(!update_at(&mut debouncer, true, 4_500));
+    assert!(!update_at(&mut debouncer, true, 9_000));
+    assert!(update_at(&mut debouncer, true, 9_500));
+}
+
+#[test]
+fn timed_handles_irregular_updates() {
+    let test_pin = TestInputPin::new(false);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::Timed { stable: 5 });
+
+    //a long gap between updates, e.g. a blocking display flush, still settles on time
+    assert!(!update_at(&mut debouncer, true, 100));
+    assert!(update_at(&mut debouncer, true, 140));
+
+    //a burst of fast updates does not settle early
+    for now in 200..205 {
+        assert!(update_at(&mut debouncer, false, now));
+    }
+    assert!(!update_at(&mut debouncer, false, 205));
+}
+
+#[test]
+fn timed_handles_clock_wrap() {
+    let test_pin = TestInputPin::new(false);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::Timed { stable: 10 });
+
+    assert!(!update_at(&mut debouncer, true, u32::MAX - 4));
+    assert!(!update_at(&mut debouncer, true, 4));
+    assert!(update_at(&mut debouncer, true, 5));
+}
+
+#[test]
+fn timed_update_counts_scans() {
+    let test_pin = TestInputPin::new(true);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::Timed { stable: 4 });
+    for _ in 0..4 {
+        debouncer.update().unwrap();
+        assert!(debouncer.is_low().unwrap());
+    }
+
+    debouncer.update().unwrap();
+    assert!(debouncer.is_high().unwrap());
+}
+
+#[test]
+fn timed_eager_changes_immediately_then_holds() {
+    let test_pin = TestInputPin::new(false);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::TimedEager { stable: 5_000 });
+
+    assert!(update_at(&mut debouncer, true, 1_000));
+
+    //bounces are held off until the line has been stable for the full duration
+    assert!(update_at(&mut debouncer, false, 1_200));
+    assert!(update_at(&mut debouncer, true, 1_400));
+    assert!(update_at(&mut debouncer, true, 6_399));
+    assert!(!update_at(&mut debouncer, false, 6_400));
+    assert!(!update_at(&mut debouncer, true, 6_500));
+}
+
+#[test]
+fn timed_eager_follows_line_that_settled_while_held() {
+    let test_pin = TestInputPin::new(false);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::TimedEager { stable: 5 });
+
+    //single sample glitch
+    assert!(update_at(&mut debouncer, true, 10));
+    assert!(update_at(&mut debouncer, false, 11));
+    assert!(update_at(&mut debouncer, false, 15));
+    assert!(!update_at(&mut debouncer, false, 16));
+}
+
+#[test]
+fn update_reports_edges_with_scan_index() {
+    let test_pin = TestInputPin::new(true);
+    let mut debouncer = DebouncedPin::new(test_pin, false);
+    for at in 1..5 {
+        assert_eq!(
+            debouncer.update().unwrap(),
+            Event {
+                edge: Edge::None,
+                at
+            }
+        );
+    }
+    assert_eq!(
+        debouncer.update().unwrap(),
+        Event {
+            edge: Edge::Rising,
+            at: 5
+        }
+    );
+    assert_eq!(debouncer.update().unwrap().edge, Edge::None);
+
+    debouncer.pin.set_value(false);
+    for _ in 0..4 {
+        assert_eq!(debouncer.update().unwrap().edge, Edge::None);
+    }
+    assert_eq!(
+        debouncer.update().unwrap(),
+        Event {
+            edge: Edge::Falling,
+            at: 11
+        }
+    );
+}
+
+#[test]
+fn update_at_reports_edges_with_timestamp() {
+    let test_pin = TestInputPin::new(true);
+    let mut debouncer =
+        DebouncedPin::with_algorithm(test_pin, false, Algorithm::TimedEager { stable: 5 });
+
+    assert_eq!(
+        debouncer.update_at(1_000).unwrap(),
+        Event {
+            edge: Edge::Rising,
+            at: 1_000
+        }
+    );
+    debouncer.pin.set_value(false);
+    assert_eq!(debouncer.update_at(1_002).unwrap().edge, Edge::None);
+    assert_eq!(
+        debouncer.update_at(1_007).unwrap(),
+        Event {
+            edge: Edge::Falling,
+            at: 1_007
+        }
+    );
+}
+
+#[test]
+fn unknown_until_window_fills() {
+    for samples in 1..=WINDOWS {
+        for algorithm in [
+            Algorithm::ShiftRegister { samples },
+            Algorithm::Integrator { samples },
+            Algorithm::Eager { samples },
+        ] {
+            for level in [false, true] {
+                let test_pin = TestInputPin::new(level);
+                let mut debouncer =
+                    DebouncedPin::with_algorithm(test_pin, DebouncerState::Unknown, algorithm);
+                for _ in 1..samples {
+                    assert_eq!(debouncer.update().unwrap().edge, Edge::None);
+                    assert_eq!(debouncer.state(), DebouncerState::Unknown);
+                    assert!(!debouncer.is_high().unwrap());
+                    assert!(!debouncer.is_low().unwrap());
+                }
+
+                assert_eq!(debouncer.update().unwrap().edge, Edge::None);
+                assert_eq!(debouncer.state(), DebouncerState::from(level));
+                assert!(debouncer.is_settled());
+            }
+        }
+    }
+}
+
+#[test]
+fn unknown_waits_for_bouncing_to_stop() {
+    let test_pin = TestInputPin::new(false);
+    let mut debouncer = DebouncedPin::with_algorithm(
+        test_pin,
+        DebouncerState::Unknown,
+        Algorithm::Eager { samples: 5 },
+    );
+    for i in 0..13 {
+        debouncer.pin.set_value(i % 3 == 0);
+        debouncer.update().unwrap();
+        assert_eq!(debouncer.state(), DebouncerState::Unknown);
+    }
+
+    debouncer.pin.set_value(false);
+    for _ in 0..4 {
+        debouncer.update().unwrap();
+        assert_eq!(debouncer.state(), DebouncerState::Unknown);
+    }
+    debouncer.update().unwrap();
+    assert_eq!(debouncer.state(), DebouncerState::Low);
+
+    //then debounces as normal
+    debouncer.pin.set_value(true);
+    assert_eq!(debouncer.update().unwrap().edge, Edge::Rising);
+}
+
+#[test]
+fn unknown_timed_waits_for_stable_duration() {
+    let test_pin = TestInputPin::new(true);
+    let mut debouncer = DebouncedPin::with_algorithm(
+        test_pin,
+        DebouncerState::Unknown,
+        Algorithm::TimedEager { stable: 5 },
+    );
+
+    assert_eq!(debouncer.update_at(1_000).unwrap().edge, Edge::None);
+    assert_eq!(debouncer.state(), DebouncerState::Unknown);
+    assert_eq!(debouncer.update_at(1_004).unwrap().edge, Edge::None);
+    assert_eq!(debouncer.state(), DebouncerState::Unknown);
+    assert_eq!(debouncer.update_at(1_005).unwrap().edge, Edge::None);
+    assert_eq!(debouncer.state(), DebouncerState::High);
+}
+
+#[test]
+fn known_start_is_settled() {
+    let debouncer = DebouncedPin::new(TestInputPin::new(false), true);
+    assert_eq!(debouncer.state(), DebouncerState::High);
+    assert!(debouncer.is_settled());
+}
+
+//xorshift, good enough to generate bouncy traces without pulling in a rng
+struct TestRng(u32);
+
+impl TestRng {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+fn bank_matches_pins<const SAMPLES: u8>() {
+    let mut rng = TestRng(0x1234_5678 ^ SAMPLES as u32);
+    let initial = rng.next();
+    let mut bank = BankDebouncer::<SAMPLES>::new(initial);
+    let mut pins: [_; 32] = core::array::from_fn(|bit| {
+        DebouncedPin::with_algorithm(
+            TestInputPin::new(false),
+            initial >> bit & 1 == 1,
+            Algorithm::ShiftRegister { samples: SAMPLES },
+        )
+    });
+
+    let mut sample = initial;
+    for _ in 0..10_000 {
+        //flip a few bits each scan so some runs are long enough to change state
+        sample ^= rng.next() & rng.next() & rng.next();
+
+        let changed = bank.update(sample);
+        for (bit, pin) in pins.iter_mut().enumerate() {
+            pin.pin.set_value(sample >> bit & 1 == 1);
+            let edge = pin.update().unwrap().edge;
+
+            assert_eq!(changed >> bit & 1 == 1, edge != Edge::None);
+            assert_eq!(bank.state() >> bit & 1 == 1, pin.is_high().unwrap());
+        }
+    }
+}
+
+#[test]
+fn bank_matches_shift_register_pins() {
+    bank_matches_pins::<1>();
+    bank_matches_pins::<2>();
+    bank_matches_pins::<3>();
+    bank_matches_pins::<4>();
+    bank_matches_pins::<5>();
+    bank_matches_pins::<6>();
+    bank_matches_pins::<7>();
+    bank_matches_pins::<8>();
+}
+
+#[test]
+fn bank_changes_after_window_consecutive_reads() {
+    let mut bank = BankDebouncer::<5>::new(0x0000_ffff);
+    for _ in 0..4 {
+        assert_eq!(bank.update(0xffff_0000), 0);
+        assert_eq!(bank.state(), 0x0000_ffff);
+    }
+    assert_eq!(bank.update(0xffff_0000), u32::MAX);
+    assert_eq!(bank.state(), 0xffff_0000);
+}
+
+#[test]
+#[should_panic]
+fn bank_rejects_window_larger_than_counter() {
+    BankDebouncer::<9>::new(0);
+}
+
+#[test]
+fn debounce_commits_value_after_samples() {
+    //2 bit quadrature state
+    let mut debounce = Debounce::new(0b11u8, Window::Samples(3));
+
+    assert_eq!(debounce.update(0b01), None);
+    assert_eq!(debounce.update(0b00), None);
+    assert_eq!(debounce.update(0b00), None);
+    assert_eq!(debounce.update(0b00), Some(0b00));
+    assert_eq!(debounce.update(0b00), None);
+    assert_eq!(debounce.value(), Some(0b00));
+
+    //a run shorter than the window of a value, then going back, changes nothing
+    assert_eq!(debounce.update(0b10), None);
+    assert_eq!(debounce.update(0b10), None);
+    assert_eq!(debounce.update(0b00), None);
+    assert_eq!(debounce.update(0b00), None);
+    assert_eq!(debounce.update(0b00), None);
+    assert_eq!(debounce.value(), Some(0b00));
+}
+
+#[test]
+fn debounce_commits_value_after_duration() {
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    enum Level {
+        Low,
+        Mid,
+        High,
+    }
+
+    let mut debounce = Debounce::new(Level::Low, Window::Duration(50));
+
+    assert_eq!(debounce.update_at(Level::Mid, 100), None);
+    assert_eq!(debounce.update_at(Level::High, 120), None);
+    assert_eq!(debounce.update_at(Level::High, 169), None);
+    assert_eq!(debounce.update_at(Level::High, 170), Some(Level::High));
+    assert_eq!(debounce.value(), Some(Level::High));
+}
+
+#[test]
+fn debounce_unknown_until_window_fills() {
+    let mut debounce = Debounce::unknown(Window::Samples(2));
+    assert_eq!(debounce.value(), None);
+
+    assert_eq!(debounce.update(7u16), None);
+    assert_eq!(debounce.update(9), None);
+    assert_eq!(debounce.value(), None);
+    assert_eq!(debounce.update(9), Some(9));
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn stats_count_bounces_and_transitions() {
+    use super::Stats;
+
+    for algorithm in [
+        Algorithm::ShiftRegister { samples: 5 },
+        Algorithm::Eager { samples: 5 },
+    ] {
+        let test_pin = TestInputPin::new(true);
+        let mut debouncer = DebouncedPin::with_algorithm(test_pin, true, algorithm);
+
+        //clean press
+        debouncer.pin.set_value(false);
+        for _ in 0..10 {
+            debouncer.update().unwrap();
+        }
+        //release with 2 bounces
+        for value in [true, false, true, false, true, true, true, true, true, true] {
+            debouncer.pin.set_value(value);
+            debouncer.update().unwrap();
+        }
+        //press with a single bounce
+        for value in [false, true, false, false, false, false, false, false] {
+            debouncer.pin.set_value(value);
+            debouncer.update().unwrap();
+        }
+        //clean release
+        debouncer.pin.set_value(true);
+        for _ in 0..10 {
+            debouncer.update().unwrap();
+        }
+
+        let stats = debouncer.stats();
+        assert_eq!(stats.bounces, 3, "{:?}", algorithm);
+        assert_eq!(stats.transitions, 4, "{:?}", algorithm);
+        assert_eq!(stats.longest_burst, 2, "{:?}", algorithm);
+
+        debouncer.reset_stats();
+        assert_eq!(debouncer.stats(), &Stats::default());
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+mod hal1 {
+    use super::TestPinError;
+    use crate::hal1::DebouncedPin;
+    use crate::{Algorithm, DebouncerState, Edge};
+    use embedded_hal_1::digital::{ErrorKind, ErrorType, InputPin};
+
+    impl embedded_hal_1::digital::Error for TestPinError {
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::Other
+        }
+    }
+
+    //plays back a fixed trace of reads, then holds the last one
+    pub(super) struct TracePin {
+        trace: &'static [bool],
+        read: usize,
+    }
+
+    impl TracePin {
+        pub(super) fn new(trace: &'static [bool]) -> TracePin {
+            TracePin { trace, read: 0 }
+        }
+        pub(super) fn reads(&self) -> usize {
+            self.read
+        }
+    }
+
+    impl ErrorType for TracePin {
+        type Error = TestPinError;
+    }
+
+    impl InputPin for TracePin {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            let value = self.trace[self.read.min(self.trace.len() - 1)];
+            self.read += 1;
+            Ok(value)
+        }
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    pub(super) const PRESS: &[bool] = &[
+        true, false, true, false, true, false, false, false, false, false, false,
+    ];
+
+    #[test]
+    fn debounces_eh1_pin() {
+        let mut debouncer = DebouncedPin::new(TracePin::new(PRESS), true);
+        for _ in 0..9 {
+            assert_eq!(debouncer.update().unwrap().edge, Edge::None);
+            assert!(debouncer.is_high().unwrap());
+        }
+        assert_eq!(debouncer.update().unwrap().edge, Edge::Falling);
+        assert!(debouncer.is_low().unwrap());
+        assert_eq!(debouncer.pin.reads(), 10);
+    }
+
+    #[test]
+    fn eh1_pin_starts_unknown() {
+        let mut debouncer = DebouncedPin::with_algorithm(
+            TracePin::new(PRESS),
+            DebouncerState::Unknown,
+            Algorithm::default(),
+        );
+        assert!(!debouncer.is_high().unwrap());
+        assert!(!debouncer.is_low().unwrap());
+        for _ in 0..10 {
+            debouncer.update().unwrap();
+        }
+        assert_eq!(debouncer.state(), DebouncerState::Low);
+    }
+}
+
+#[cfg(feature = "async")]
+mod asynch {
+    use super::hal1::{TracePin, PRESS};
+    use crate::hal1::{AsyncDebouncedPin, DebouncedPin};
+    use crate::{Algorithm, DebouncerState};
+    use core::future::Future;
+    use core::task::{Context, Poll, Waker};
+    use embedded_hal_async::delay::DelayNs;
+    use embedded_hal_async::digital::Wait;
+
+    struct NoDelay;
+
+    impl DelayNs for NoDelay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = core::pin::pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn falling_edge_waits_for_debounced_edge() {
+        let pin = DebouncedPin::new(TracePin::new(PRESS), true);
+        let mut pin = AsyncDebouncedPin::new(pin, NoDelay, 1_000);
+
+        block_on(pin.wait_for_falling_edge()).unwrap();
+        assert_eq!(pin.pin_borrow().pin.reads(), 10);
+    }
+
+    #[test]
+    fn wait_for_low_returns_once_settled_low() {
+        let pin = DebouncedPin::with_algorithm(
+            TracePin::new(PRESS),
+            DebouncerState::Unknown,
+            Algorithm::default(),
+        );
+        let mut pin = AsyncDebouncedPin::new(pin, NoDelay, 1_000);
+
+        block_on(pin.wait_for_low()).unwrap();
+        assert_eq!(pin.pin_borrow().pin.reads(), 10);
+
+        //already low
+        block_on(pin.wait_for_low()).unwrap();
+        assert_eq!(pin.pin_borrow().pin.reads(), 10);
+    }
+}
@@ -0,0 +1,81 @@
+/// How long a new value has to be seen before it is committed
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Window {
+    /// Seen on this many updates in a row
+    Samples(u32),
+    /// Seen on every update for this many ticks of the update timestamp
+    Duration(u32),
+}
+
+/// Debounces any sampled value, e.g. an ADC threshold level or a quadrature state
+pub struct Debounce<T> {
+    window: Window,
+    value: Option<T>,
+    candidate: Option<T>,
+    run: u32,
+    since: u32,
+    //timestamp of the latest update, counts updates when driven by `update`
+    now: u32,
+}
+
+impl<T> Debounce<T>
+where
+    T: PartialEq + Copy,
+{
+    pub fn new(initial: T, window: Window) -> Debounce<T> {
+        Debounce {
+            window,
+            value: Some(initial),
+            candidate: Some(initial),
+            run: 0,
+            since: 0,
+            now: 0,
+        }
+    }
+
+    /// Start with no value until the first full window of agreeing samples
+    pub fn unknown(window: Window) -> Debounce<T> {
+        Debounce {
+            window,
+            value: None,
+            candidate: None,
+            run: 0,
+            since: 0,
+            now: 0,
+        }
+    }
+
+    pub fn value(&self) -> Option<T> {
+        self.value
+    }
+
+    /// Add a sample, treating each call as one tick, returning the value if a new one was committed
+    pub fn update(&mut self, sample: T) -> Option<T> {
+        self.update_at(sample, self.now.wrapping_add(1))
+    }
+
+    /// Add a sample taken at a timestamp from a free running, wrapping clock, returning the
+    /// value if a new one was committed
+    pub fn update_at(&mut self, sample: T, now: u32) -> Option<T> {
+        self.now = now;
+
+        if self.candidate != Some(sample) {
+            self.candidate = Some(sample);
+            self.run = 0;
+            self.since = now;
+        }
+        self.run = self.run.saturating_add(1);
+
+        let stable = match self.window {
+            Window::Samples(samples) => self.run >= samples,
+            Window::Duration(duration) => now.wrapping_sub(self.since) >= duration,
+        };
+
+        if stable && self.value != Some(sample) {
+            self.value = Some(sample);
+            self.value
+        } else {
+            None
+        }
+    }
+}
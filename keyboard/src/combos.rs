@@ -0,0 +1,324 @@
+use crate::keycode::KeyCode;
+use crate::{KeyAction, KeyEvent, KeyboardLayout, OneShots, Outputs};
+use arrayvec::ArrayVec;
+
+//keys are tracked in a u32 mask, so only the first 32 can be in combos
+const COMBO_KEYS: usize = 32;
+
+/// Keys pressed together to fire an action in place of their own
+#[derive(Debug, Eq, PartialEq)]
+pub struct Combo {
+    pub keys: &'static [usize],
+    pub action: KeyAction,
+    /// Time in ms from the first key press to complete the combo, overriding the layout's timeout
+    pub term: Option<u32>,
+    /// Bit per layer the combo works on when it is the highest active layer, or any layer
+    pub layers: Option<u32>,
+}
+
+impl Combo {
+    pub const fn new(keys: &'static [usize], action: KeyAction) -> Combo {
+        Combo {
+            keys,
+            action,
+            term: None,
+            layers: None,
+        }
+    }
+
+    pub const fn term(self, term: u32) -> Combo {
+        Combo {
+            term: Some(term),
+            ..self
+        }
+    }
+
+    /// Only work on these layers, a bit per layer
+    pub const fn layers(self, layers: u32) -> Combo {
+        Combo {
+            layers: Some(layers),
+            ..self
+        }
+    }
+
+    fn mask(&self) -> u32 {
+        self.keys.iter().fold(0, |mask, &key| mask | 1 << key)
+    }
+}
+
+/// What to do when a completed combo is part of a longer one
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum ComboOverlap {
+    /// Wait for the longer combo until it can no longer complete
+    #[default]
+    Longest,
+    /// Fire the first combo completed
+    Shortest,
+}
+
+//keys held for a combo that has fired
+struct Active {
+    combo: usize,
+    held: u32,
+    released: bool,
+}
+
+/// Holds back presses of combo keys until they complete a combo or can't, passing other keys
+/// on to the wrapped layout in order
+pub struct ComboLayout<KL> {
+    layout: KL,
+    combos: &'static [Combo],
+    timeout: u32,
+    overlap: ComboOverlap,
+    buffered: ArrayVec<KeyEvent, 32>,
+    //combo completed by the buffered keys, while waiting for a longer one
+    completed: Option<usize>,
+    active: ArrayVec<Active, 8>,
+}
+
+impl<KL> ComboLayout<KL> {
+    pub const DEFAULT_TIMEOUT: u32 = 50;
+
+    pub fn new(layout: KL, combos: &'static [Combo]) -> ComboLayout<KL> {
+        assert!(
+            combos
+                .iter()
+                .all(|c| c.keys.iter().all(|&k| k < COMBO_KEYS)),
+            "combo keys must be 0..32"
+        );
+
+        ComboLayout {
+            layout,
+            combos,
+            timeout: ComboLayout::<KL>::DEFAULT_TIMEOUT,
+            overlap: ComboOverlap::default(),
+            buffered: ArrayVec::new(),
+            completed: None,
+            active: ArrayVec::new(),
+        }
+    }
+
+    /// Time in ms from the first key press to complete a combo
+    pub fn timeout(self, timeout: u32) -> ComboLayout<KL> {
+        ComboLayout { timeout, ..self }
+    }
+
+    pub fn overlap(self, overlap: ComboOverlap) -> ComboLayout<KL> {
+        ComboLayout { overlap, ..self }
+    }
+
+    pub fn layout(&self) -> &KL {
+        &self.layout
+    }
+
+    //keys pressed since the first buffered press, releases of other keys wait behind them
+    fn buffered_mask(&self) -> u32 {
+        self.buffered
+            .iter()
+            .filter(|e| e.pressed)
+            .fold(0, |mask, e| mask | 1 << e.key)
+    }
+
+    fn term(&self, combo: &Combo) -> u32 {
+        combo.term.unwrap_or(self.timeout)
+    }
+
+    //combos the keys in `mask` could still complete at `now`
+    fn alive<const N: usize>(
+        &self,
+        mask: u32,
+        now: u32,
+    ) -> impl Iterator<Item = (usize, &'static Combo)> + '_
+    where
+        KL: KeyboardLayout<N>,
+    {
+        let layer = 1 << self.layout.active_layer();
+        let elapsed = self
+            .buffered
+            .first()
+            .map_or(0, |first| now.wrapping_sub(first.at));
+        //keys still held for a fired combo can't take part in another
+        let held = self.active.iter().fold(0, |held, a| held | a.held);
+
+        self.combos.iter().enumerate().filter(move |(_, c)| {
+            c.mask() & mask == mask
+                && c.mask() & held == 0
+                && c.layers.is_none_or(|layers| layers & layer != 0)
+                && elapsed < self.term(c)
+        })
+    }
+}
+
+impl<KL, const N: usize> KeyboardLayout<N> for ComboLayout<KL>
+where
+    KL: KeyboardLayout<N>,
+{
+    fn event(&mut self, event: &KeyEvent, output: &mut Outputs) {
+        self.tick_combos(event.at, output);
+
+        if event.key >= COMBO_KEYS {
+            //never part of a combo, but still interrupts one being typed
+            if event.pressed && !self.buffered.is_empty() {
+                self.resolve(output);
+            }
+            self.pass(event, output);
+        } else if event.pressed {
+            self.press(event, output);
+        } else {
+            self.release(event, output);
+        }
+    }
+
+    fn trigger(&mut self, action: KeyAction, pressed: bool, at: u32, output: &mut Outputs) {
+        self.layout.trigger(action, pressed, at, output);
+    }
+
+    fn tick(&mut self, now: u32, output: &mut Outputs) {
+        self.tick_combos(now, output);
+        self.layout.tick(now, output);
+    }
+
+    fn leader(&self) -> Option<&[KeyCode]> {
+        self.layout.leader()
+    }
+
+    fn one_shots(&self) -> OneShots {
+        self.layout.one_shots()
+    }
+
+    fn active_layer(&self) -> u8 {
+        self.layout.active_layer()
+    }
+}
+
+impl<KL> ComboLayout<KL> {
+    fn press<const N: usize>(&mut self, event: &KeyEvent, output: &mut Outputs)
+    where
+        KL: KeyboardLayout<N>,
+    {
+        let mask = self.buffered_mask() | 1 << event.key;
+
+        if self.alive::<N>(mask, event.at).next().is_none() {
+            //can't be part of the buffered combo, settle it and start again with this key
+            if !self.buffered.is_empty() {
+                self.resolve(output);
+                self.press(event, output);
+            } else {
+                self.layout.event(event, output);
+            }
+            return;
+        }
+
+        if self.buffered.try_push(*event).is_err() {
+            //full of releases waiting behind the buffered presses
+            self.resolve(output);
+            self.press(event, output);
+            return;
+        }
+
+        let mut completed = self.completed;
+        let mut longer = false;
+        for (i, combo) in self.alive::<N>(mask, event.at) {
+            if combo.mask() == mask {
+                completed = Some(i);
+            } else {
+                longer = true;
+            }
+        }
+        self.completed = completed;
+        if completed.is_some() && (self.overlap == ComboOverlap::Shortest || !longer) {
+            self.resolve(output);
+        }
+    }
+
+    fn release<const N: usize>(&mut self, event: &KeyEvent, output: &mut Outputs)
+    where
+        KL: KeyboardLayout<N>,
+    {
+        let bit = 1 << event.key;
+
+        if let Some(i) = self.active.iter().position(|a| a.held & bit != 0) {
+            let active = &mut self.active[i];
+            active.held &= !bit;
+            //the first key up releases the action, the others are swallowed
+            if !active.released {
+                active.released = true;
+                let action = self.combos[active.combo].action;
+                self.layout.trigger(action, false, event.at, output);
+            }
+            if self.active[i].held == 0 {
+                self.active.remove(i);
+            }
+            return;
+        }
+
+        if self.buffered_mask() & bit != 0 {
+            self.resolve(output);
+            self.release(event, output);
+            return;
+        }
+
+        self.pass(event, output);
+    }
+
+    //pass on a key outside the combo being typed, a release waiting behind the buffered presses
+    fn pass<const N: usize>(&mut self, event: &KeyEvent, output: &mut Outputs)
+    where
+        KL: KeyboardLayout<N>,
+    {
+        if event.pressed || self.buffered.is_empty() {
+            self.layout.event(event, output);
+        } else if self.buffered.try_push(*event).is_err() {
+            self.resolve(output);
+            self.layout.event(event, output);
+        }
+    }
+
+    fn tick_combos<const N: usize>(&mut self, now: u32, output: &mut Outputs)
+    where
+        KL: KeyboardLayout<N>,
+    {
+        if !self.buffered.is_empty() && self.alive::<N>(self.buffered_mask(), now).next().is_none()
+        {
+            self.resolve(output);
+        }
+    }
+
+    //fire the completed combo, passing on the other buffered events in order
+    fn resolve<const N: usize>(&mut self, output: &mut Outputs)
+    where
+        KL: KeyboardLayout<N>,
+    {
+        let buffered = core::mem::take(&mut self.buffered);
+        let mut held = 0;
+
+        if let Some(i) = self.completed.take() {
+            let combo = &self.combos[i];
+            held = combo.mask();
+            let at = buffered
+                .iter()
+                .rev()
+                .find(|e| e.pressed && held & 1 << e.key != 0)
+                .map_or(0, |e| e.at);
+
+            self.layout.trigger(combo.action, true, at, output);
+            if self.active.is_full() {
+                //nowhere to track the keys, release straight away rather than leave it stuck
+                self.layout.trigger(combo.action, false, at, output);
+            } else {
+                self.active.push(Active {
+                    combo: i,
+                    held,
+                    released: false,
+                });
+            }
+        }
+
+        for event in buffered
+            .iter()
+            .filter(|e| !e.pressed || held & 1 << e.key == 0)
+        {
+            self.layout.event(event, output);
+        }
+    }
+}
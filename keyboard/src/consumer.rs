@@ -0,0 +1,13 @@
+//Usages for KeyAction::Consumer, values taken from USB HID Usage Tables - https://www.usb.org/sites/default/files/documents/hut1_12v2.pdf p75 Consumer Page (0x0C)
+
+pub const BRIGHTNESS_UP: u16 = 0x6F;
+pub const BRIGHTNESS_DOWN: u16 = 0x70;
+pub const NEXT_TRACK: u16 = 0xB5;
+pub const PREVIOUS_TRACK: u16 = 0xB6;
+pub const STOP: u16 = 0xB7;
+pub const PLAY_PAUSE: u16 = 0xCD;
+pub const MUTE: u16 = 0xE2;
+pub const VOLUME_UP: u16 = 0xE9;
+pub const VOLUME_DOWN: u16 = 0xEA;
+/// AL Calculator
+pub const CALCULATOR: u16 = 0x192;
@@ -0,0 +1,488 @@
+use crate::keycode::{KeyCode, Modifiers};
+use crate::{
+    KeyAction, KeyEvent, KeyboardLayout, Leader, LeaderKeys, OneShots, Output, Outputs, TapDance,
+    TapHold,
+};
+use arrayvec::ArrayVec;
+
+//key events held back while a tap-hold key is undecided
+const MAX_BUFFERED: usize = 16;
+
+#[derive(Copy, Clone)]
+struct Pending {
+    key: usize,
+    tap_hold: &'static TapHold,
+    at: u32,
+}
+
+#[derive(Copy, Clone)]
+struct Dance {
+    key: usize,
+    tap_dance: &'static TapDance,
+    count: u8,
+    pressed: bool,
+    //time of the last press or release
+    at: u32,
+}
+
+struct Leading {
+    keys: LeaderKeys,
+    //time of the leader key or the last key typed after it
+    at: u32,
+}
+
+/// Stack of keymaps, where `Transparent` falls through to the next active layer down
+pub struct LayeredKeyboardLayout<const N: usize, const LAYERS: usize> {
+    keymaps: [[KeyAction; N]; LAYERS],
+    default_layer: u8,
+    //bit per layer switched on by MO, TG and TO, the default layer and one-shot layers are on too
+    active: u32,
+    //action each held key resolved to when pressed, so it is released with the same action
+    pressed: [Option<KeyAction>; N],
+    pending: Option<Pending>,
+    buffered: ArrayVec<KeyEvent, MAX_BUFFERED>,
+    //tap-hold key held past its term with no other key pressed, and its tap action
+    retro: Option<(usize, KeyAction)>,
+    dance: Option<Dance>,
+    leaders: &'static [Leader],
+    leader_timeout: u32,
+    leading: Option<Leading>,
+    one_shots: OneShots,
+    //key that used up one-shot modifiers, or None for a triggered action such as a combo's,
+    //which are released with it or with the next key press, so a rolled key goes without them
+    one_shot_key: Option<(Option<usize>, Modifiers)>,
+}
+
+impl<const N: usize, const LAYERS: usize> LayeredKeyboardLayout<N, LAYERS> {
+    pub fn new(keymaps: [[KeyAction; N]; LAYERS]) -> LayeredKeyboardLayout<N, LAYERS> {
+        assert!(
+            (1..=32).contains(&LAYERS),
+            "layered keyboard layout must have 1..=32 layers"
+        );
+
+        LayeredKeyboardLayout {
+            keymaps,
+            default_layer: 0,
+            active: 0,
+            pressed: [None; N],
+            pending: None,
+            buffered: ArrayVec::new(),
+            retro: None,
+            dance: None,
+            leaders: &[],
+            leader_timeout: Leader::DEFAULT_TIMEOUT,
+            leading: None,
+            one_shots: OneShots::default(),
+            one_shot_key: None,
+        }
+    }
+
+    /// Sequences typed after a `Leader` key
+    pub fn leaders(self, leaders: &'static [Leader]) -> LayeredKeyboardLayout<N, LAYERS> {
+        LayeredKeyboardLayout { leaders, ..self }
+    }
+
+    /// Time in ms to type each key of a leader sequence
+    pub fn leader_timeout(self, leader_timeout: u32) -> LayeredKeyboardLayout<N, LAYERS> {
+        LayeredKeyboardLayout {
+            leader_timeout,
+            ..self
+        }
+    }
+
+    pub fn default_layer(&self) -> u8 {
+        self.default_layer
+    }
+
+    pub fn is_active(&self, layer: u8) -> bool {
+        self.layers() & Self::bit(layer) != 0
+    }
+
+    /// Highest layer switched on
+    pub fn active_layer(&self) -> u8 {
+        31 - self.layers().leading_zeros() as u8
+    }
+
+    /// Action a key press would resolve to with the current layers
+    pub fn action(&self, key: usize) -> KeyAction {
+        let layers = self.layers();
+
+        (0..LAYERS)
+            .rev()
+            .filter(|&layer| layers & Self::bit(layer as u8) != 0)
+            .map(|layer| self.keymaps[layer][key])
+            .find(|&action| action != KeyAction::Transparent)
+            .unwrap_or(KeyAction::NoOp)
+    }
+
+    fn layers(&self) -> u32 {
+        self.active
+            | Self::bit(self.default_layer)
+            | self.one_shots.layers
+            | self.one_shots.locked_layers
+    }
+
+    fn bit(layer: u8) -> u32 {
+        if (layer as usize) < LAYERS {
+            1 << layer
+        } else {
+            0
+        }
+    }
+
+    fn press(&mut self, event: &KeyEvent, output: &mut Outputs) {
+        if self.retro.is_some_and(|(key, _)| key != event.key) {
+            self.retro = None;
+        }
+
+        if let Some(dance) = self.dance {
+            if dance.key == event.key {
+                self.dance = Some(Dance {
+                    count: dance.count.saturating_add(1),
+                    pressed: true,
+                    at: event.at,
+                    ..dance
+                });
+                return;
+            }
+            //another key cuts the dance short
+            self.finish_dance(true, output);
+        }
+
+        if self.leading.is_some() {
+            if let KeyAction::Key { code, .. } = self.action(event.key) {
+                self.lead(code, event.at, output);
+                return;
+            }
+            //anything but a plain key gives up on the sequence
+            self.leading = None;
+        }
+
+        match self.action(event.key) {
+            KeyAction::TapHold(tap_hold) => {
+                self.pending = Some(Pending {
+                    key: event.key,
+                    tap_hold,
+                    at: event.at,
+                })
+            }
+            KeyAction::TapDance(tap_dance) => {
+                self.dance = Some(Dance {
+                    key: event.key,
+                    tap_dance,
+                    count: 1,
+                    pressed: true,
+                    at: event.at,
+                })
+            }
+            KeyAction::Leader => {
+                self.leading = Some(Leading {
+                    keys: LeaderKeys::new(),
+                    at: event.at,
+                })
+            }
+            action => self.press_action(event.key, action, output),
+        }
+    }
+
+    //one-shots wait through modifiers and other one-shots for a key to apply to
+    fn uses_one_shots(action: KeyAction) -> bool {
+        match action {
+            KeyAction::Key { code, .. } => !code.is_modifier(),
+            KeyAction::OneShotMod(_)
+            | KeyAction::OneShotLayer(_)
+            | KeyAction::MomentaryLayer(_)
+            | KeyAction::ToggleLayer(_)
+            | KeyAction::ToLayer(_)
+            | KeyAction::DefaultLayer(_) => false,
+            _ => true,
+        }
+    }
+
+    fn use_one_shots(&mut self, key: Option<usize>) {
+        let modifiers = core::mem::take(&mut self.one_shots.modifiers);
+        if !modifiers.is_empty() {
+            self.one_shot_key = Some((key, modifiers));
+        }
+        self.one_shots.layers = 0;
+    }
+
+    fn one_shot_mod(&mut self, modifiers: Modifiers, output: &mut Outputs) {
+        let one_shots = &mut self.one_shots;
+        if one_shots.locked_modifiers.contains(modifiers) {
+            one_shots.locked_modifiers.remove(modifiers);
+            for code in modifiers.keycodes() {
+                output.try_push(Output::Release(code)).ok();
+            }
+        } else if one_shots.modifiers.contains(modifiers) {
+            one_shots.modifiers.remove(modifiers);
+            one_shots.locked_modifiers.insert(modifiers);
+        } else {
+            let new = modifiers - one_shots.modifiers - one_shots.locked_modifiers;
+            one_shots.modifiers.insert(modifiers);
+            for code in new.keycodes() {
+                output.try_push(Output::Press(code)).ok();
+            }
+        }
+    }
+
+    fn one_shot_layer(&mut self, layer: u8) {
+        let bit = Self::bit(layer);
+        let one_shots = &mut self.one_shots;
+        if one_shots.locked_layers & bit != 0 {
+            one_shots.locked_layers &= !bit;
+        } else if one_shots.layers & bit != 0 {
+            one_shots.layers &= !bit;
+            one_shots.locked_layers |= bit;
+        } else {
+            one_shots.layers |= bit;
+        }
+    }
+
+    //add a key to the leader sequence, firing it once no longer sequence can match
+    fn lead(&mut self, code: KeyCode, at: u32, output: &mut Outputs) {
+        let Some(leading) = self.leading.as_mut() else {
+            return;
+        };
+        if leading.keys.try_push(code).is_err() {
+            self.leading = None;
+            return;
+        }
+        leading.at = at;
+
+        let keys = &leading.keys;
+        let mut longer = self
+            .leaders
+            .iter()
+            .filter(|l| l.sequence.len() > keys.len() && l.sequence.starts_with(keys));
+        if longer.next().is_none() {
+            self.finish_leader(output);
+        }
+    }
+
+    fn finish_leader(&mut self, output: &mut Outputs) {
+        if let Some(leading) = self.leading.take() {
+            let leader = self
+                .leaders
+                .iter()
+                .find(|l| l.sequence == leading.keys.as_slice());
+            if let Some(leader) = leader {
+                self.press_triggered(leader.action, output);
+                self.release_triggered(leader.action, output);
+            }
+        }
+    }
+
+    fn press_action(&mut self, key: usize, action: KeyAction, output: &mut Outputs) {
+        self.release_one_shot_mods(output);
+        self.apply_press(action, output);
+        self.pressed[key] = Some(action);
+        if Self::uses_one_shots(action) {
+            self.use_one_shots(Some(key));
+        }
+    }
+
+    //press an action that is not on a key, using up one-shots like a key press
+    fn press_triggered(&mut self, action: KeyAction, output: &mut Outputs) {
+        self.release_one_shot_mods(output);
+        self.apply_press(action, output);
+        if Self::uses_one_shots(action) {
+            self.use_one_shots(None);
+        }
+    }
+
+    fn release_triggered(&mut self, action: KeyAction, output: &mut Outputs) {
+        self.apply_release(action, output);
+        if self.one_shot_key.is_some_and(|(key, _)| key.is_none()) {
+            self.release_one_shot_mods(output);
+        }
+    }
+
+    //release the modifiers used up by a key, except those locked or set again since
+    fn release_one_shot_mods(&mut self, output: &mut Outputs) {
+        if let Some((_, modifiers)) = self.one_shot_key.take() {
+            let still_on = self.one_shots.modifiers | self.one_shots.locked_modifiers;
+            for code in (modifiers - still_on).keycodes() {
+                output.try_push(Output::Release(code)).ok();
+            }
+        }
+    }
+
+    fn apply_press(&mut self, action: KeyAction, output: &mut Outputs) {
+        match action {
+            KeyAction::MomentaryLayer(layer) => self.active |= Self::bit(layer),
+            KeyAction::ToggleLayer(layer) => self.active ^= Self::bit(layer),
+            KeyAction::ToLayer(layer) => {
+                self.active = Self::bit(layer);
+                self.one_shots.layers = 0;
+                self.one_shots.locked_layers = 0;
+            }
+            KeyAction::DefaultLayer(layer) => {
+                if Self::bit(layer) != 0 {
+                    self.default_layer = layer;
+                }
+            }
+            KeyAction::OneShotMod(modifiers) => self.one_shot_mod(modifiers, output),
+            KeyAction::OneShotLayer(layer) => self.one_shot_layer(layer),
+            _ => {
+                if let Some(change) = Output::of(action, true) {
+                    output.try_push(change).ok();
+                }
+            }
+        }
+    }
+
+    fn apply_release(&mut self, action: KeyAction, output: &mut Outputs) {
+        match action {
+            KeyAction::MomentaryLayer(layer) => self.active &= !Self::bit(layer),
+            _ => {
+                if let Some(change) = Output::of(action, false) {
+                    output.try_push(change).ok();
+                }
+            }
+        }
+    }
+
+    fn release(&mut self, key: usize, output: &mut Outputs) {
+        if let Some(action) = self.pressed[key].take() {
+            self.apply_release(action, output);
+        }
+
+        if self.one_shot_key.is_some_and(|(k, _)| k == Some(key)) {
+            self.release_one_shot_mods(output);
+        }
+
+        if let Some((_, tap)) = self.retro.filter(|&(k, _)| k == key) {
+            self.retro = None;
+            self.press_action(key, tap, output);
+            self.release(key, output);
+        }
+    }
+
+    fn process(&mut self, event: &KeyEvent, output: &mut Outputs) {
+        if event.pressed {
+            self.press(event, output);
+        } else if let Some(dance) = self.dance.filter(|d| d.key == event.key && d.pressed) {
+            self.dance = Some(Dance {
+                pressed: false,
+                at: event.at,
+                ..dance
+            });
+            if dance.tap_dance.is_final(dance.count) {
+                self.finish_dance(false, output);
+            }
+        } else {
+            self.release(event.key, output);
+        }
+    }
+
+    //pick the action for the taps so far, held while the key is still down
+    fn finish_dance(&mut self, interrupted: bool, output: &mut Outputs) {
+        if let Some(dance) = self.dance.take() {
+            let action = if dance.pressed && !interrupted {
+                dance.tap_dance.held(dance.count)
+            } else {
+                dance.tap_dance.tap(dance.count)
+            };
+
+            self.press_action(dance.key, action, output);
+            if !dance.pressed {
+                self.release(dance.key, output);
+            }
+        }
+    }
+
+    //decide an undecided tap-hold key from the events seen while it is down
+    fn pending_event(&mut self, pending: Pending, event: &KeyEvent, output: &mut Outputs) {
+        if event.key == pending.key {
+            //released within the tapping term
+            self.resolve(pending.tap_hold.tap, output);
+            self.process(event, output);
+            return;
+        }
+
+        if self.buffered.try_push(*event).is_err() {
+            self.resolve(pending.tap_hold.hold, output);
+            self.event(event, output);
+            return;
+        }
+
+        let hold = if event.pressed {
+            pending.tap_hold.hold_on_other_key_press
+        } else {
+            pending.tap_hold.permissive_hold
+                && self
+                    .buffered
+                    .iter()
+                    .any(|e| e.key == event.key && e.pressed)
+        };
+        if hold {
+            self.resolve(pending.tap_hold.hold, output);
+        }
+    }
+
+    fn resolve(&mut self, action: KeyAction, output: &mut Outputs) {
+        if let Some(pending) = self.pending.take() {
+            self.press_action(pending.key, action, output);
+
+            let buffered = core::mem::take(&mut self.buffered);
+            for event in &buffered {
+                self.event(event, output);
+            }
+        }
+    }
+}
+
+impl<const N: usize, const LAYERS: usize> KeyboardLayout<N> for LayeredKeyboardLayout<N, LAYERS> {
+    fn event(&mut self, event: &KeyEvent, output: &mut Outputs) {
+        self.tick(event.at, output);
+
+        match self.pending {
+            Some(pending) => self.pending_event(pending, event, output),
+            None => self.process(event, output),
+        }
+    }
+
+    fn trigger(&mut self, action: KeyAction, pressed: bool, _at: u32, output: &mut Outputs) {
+        if pressed {
+            self.press_triggered(action, output);
+        } else {
+            self.release_triggered(action, output);
+        }
+    }
+
+    fn tick(&mut self, now: u32, output: &mut Outputs) {
+        if let Some(pending) = self.pending {
+            if now.wrapping_sub(pending.at) >= pending.tap_hold.tapping_term {
+                if pending.tap_hold.retro_tapping && !self.buffered.iter().any(|e| e.pressed) {
+                    self.retro = Some((pending.key, pending.tap_hold.tap));
+                }
+                self.resolve(pending.tap_hold.hold, output);
+            }
+        }
+
+        if let Some(dance) = self.dance {
+            if now.wrapping_sub(dance.at) >= dance.tap_dance.term {
+                self.finish_dance(false, output);
+            }
+        }
+
+        if let Some(leading) = &self.leading {
+            if now.wrapping_sub(leading.at) >= self.leader_timeout {
+                self.finish_leader(output);
+            }
+        }
+    }
+
+    fn leader(&self) -> Option<&[KeyCode]> {
+        self.leading.as_ref().map(|l| l.keys.as_slice())
+    }
+
+    fn one_shots(&self) -> OneShots {
+        self.one_shots
+    }
+
+    fn active_layer(&self) -> u8 {
+        LayeredKeyboardLayout::active_layer(self)
+    }
+}
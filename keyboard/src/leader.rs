@@ -0,0 +1,24 @@
+use crate::keycode::KeyCode;
+use crate::KeyAction;
+use arrayvec::ArrayVec;
+
+/// Most keys typed after a leader key
+pub const MAX_LEADER_KEYS: usize = 8;
+
+pub type LeaderKeys = ArrayVec<KeyCode, MAX_LEADER_KEYS>;
+
+/// Sequence of key codes typed after a leader key, and the action it fires
+#[derive(Debug, Eq, PartialEq)]
+pub struct Leader {
+    pub sequence: &'static [KeyCode],
+    pub action: KeyAction,
+}
+
+impl Leader {
+    /// Time in ms to type each key of a sequence
+    pub const DEFAULT_TIMEOUT: u32 = 1000;
+
+    pub const fn new(sequence: &'static [KeyCode], action: KeyAction) -> Leader {
+        Leader { sequence, action }
+    }
+}
@@ -0,0 +1,622 @@
+#![no_std]
+
+use crate::keycode::KeyCode;
+use crate::keycode::Modifiers;
+use arrayvec::ArrayVec;
+use debounce::{Algorithm, DebouncedPin, DebouncerState, Edge};
+use embedded_hal::digital::v2::InputPin;
+
+mod combos;
+pub mod consumer;
+pub mod keycode;
+mod layers;
+mod leader;
+mod macros;
+mod mouse;
+mod one_shot;
+pub mod report;
+pub mod system;
+mod tap_dance;
+mod tap_hold;
+mod text;
+mod unicode;
+
+pub use combos::{Combo, ComboLayout, ComboOverlap};
+pub use layers::LayeredKeyboardLayout;
+pub use leader::{Leader, LeaderKeys, MAX_LEADER_KEYS};
+pub use macros::MacroStep;
+pub use mouse::{
+    Acceleration, Curve, MouseAction, MouseReport, BUTTON_LEFT, BUTTON_MIDDLE, BUTTON_RIGHT,
+};
+pub use one_shot::OneShots;
+pub use tap_dance::TapDance;
+pub use tap_hold::TapHold;
+pub use text::{HostLayout, Stroke, TextError};
+pub use unicode::UnicodeMode;
+
+use crate::macros::MacroPlayer;
+use crate::mouse::Mouse;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum KeyAction {
+    /// Key with modifiers that are held only while it is the last key pressed
+    Key {
+        code: KeyCode,
+        modifiers: Modifiers,
+    },
+    /// Switch a layer on while held (MO)
+    MomentaryLayer(u8),
+    /// Switch a layer on or off on each press (TG)
+    ToggleLayer(u8),
+    /// Switch a layer on and every other layer above the default off (TO)
+    ToLayer(u8),
+    /// Change the base layer (DF)
+    DefaultLayer(u8),
+    /// One action when tapped and another when held
+    TapHold(&'static TapHold),
+    /// Action picked by the number of taps in a row
+    TapDance(&'static TapDance),
+    /// Start a sequence of keys matched against the layout's leader sequences
+    Leader,
+    /// Consumer page usage such as volume or play/pause, see `consumer`
+    Consumer(u16),
+    /// System control usage such as sleep, see `system`
+    System(u8),
+    /// Mouse button, pointer movement or wheel scrolling
+    Mouse(MouseAction),
+    /// Modifiers for the next key, locked on when tapped twice
+    OneShotMod(Modifiers),
+    /// Layer for the next key, locked on when tapped twice
+    OneShotLayer(u8),
+    /// Play a sequence of steps on press
+    Macro(&'static [MacroStep]),
+    /// Type a character with the keyboard's unicode input method
+    Unicode(char),
+    /// Change the unicode input method
+    UnicodeMode(UnicodeMode),
+    /// Use the action from the next active layer down
+    Transparent,
+    NoOp,
+}
+
+#[derive(Default, Copy, Clone)]
+pub struct KeyState {
+    pub pressed: bool,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct KeyEvent {
+    pub key: usize,
+    pub pressed: bool,
+    pub at: u32,
+}
+
+pub type KeyEvents<const KEY_COUNT: usize> = ArrayVec<KeyEvent, KEY_COUNT>;
+
+pub trait KeyboardMatrix<const KEY_COUNT: usize> {
+    type Error;
+    /// Scan the keys, returning any presses and releases since the last update
+    fn update(&mut self, now: u32) -> Result<KeyEvents<KEY_COUNT>, Self::Error>;
+    fn keys(&self) -> Result<[KeyState; KEY_COUNT], Self::Error>;
+}
+
+pub struct DirectPinMatrix<P, const N: usize> {
+    pins: [DebouncedPin<P>; N],
+}
+
+impl<P, const N: usize> DirectPinMatrix<P, N> {
+    pub fn new(pins: [P; N]) -> DirectPinMatrix<P, N>
+    where
+        P: InputPin,
+    {
+        Self::with_algorithm(pins, Algorithm::default())
+    }
+
+    pub fn with_algorithm(pins: [P; N], algorithm: Algorithm) -> DirectPinMatrix<P, N>
+    where
+        P: InputPin,
+    {
+        DirectPinMatrix {
+            pins: pins.map(|p| DebouncedPin::with_algorithm(p, DebouncerState::Unknown, algorithm)),
+        }
+    }
+
+    pub fn is_settled(&self) -> bool
+    where
+        P: InputPin,
+    {
+        self.pins.iter().all(|p| p.is_settled())
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> [debounce::Stats; N]
+    where
+        P: InputPin,
+    {
+        let mut stats = [debounce::Stats::default(); N];
+        for (s, p) in stats.iter_mut().zip(&self.pins) {
+            *s = *p.stats();
+        }
+        stats
+    }
+}
+
+impl<P, const N: usize> KeyboardMatrix<N> for DirectPinMatrix<P, N>
+where
+    P: InputPin,
+{
+    type Error = P::Error;
+
+    fn keys(&self) -> Result<[KeyState; N], Self::Error> {
+        let mut keystates = [KeyState::default(); N];
+
+        //report nothing pressed until every key has settled after power up
+        if !self.is_settled() {
+            return Ok(keystates);
+        }
+
+        for (i, p) in self.pins.iter().enumerate() {
+            keystates[i].pressed = p.is_low()?;
+        }
+        Ok(keystates)
+    }
+
+    fn update(&mut self, now: u32) -> Result<KeyEvents<N>, Self::Error> {
+        let mut events = KeyEvents::new();
+        let settled = self.is_settled();
+
+        for (key, p) in self.pins.iter_mut().enumerate() {
+            let event = p.update_at(now)?;
+            if !settled {
+                continue;
+            }
+            //keys are active low
+            let pressed = match event.edge {
+                Edge::Falling => true,
+                Edge::Rising => false,
+                Edge::None => continue,
+            };
+            events.push(KeyEvent {
+                key,
+                pressed,
+                at: event.at,
+            });
+        }
+        Ok(events)
+    }
+}
+
+/// Change to the keys reported to the host
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Output {
+    Press(KeyCode),
+    /// Press a key with modifiers that are masked once another key is pressed
+    PressWith(KeyCode, Modifiers),
+    Release(KeyCode),
+    PressConsumer(u16),
+    ReleaseConsumer(u16),
+    PressSystem(u8),
+    ReleaseSystem(u8),
+    PressMouse(MouseAction),
+    ReleaseMouse(MouseAction),
+    /// Wait in ms before reporting later changes
+    Delay(u32),
+    Macro(&'static [MacroStep]),
+    /// Type text with the keyboard's host layout
+    Text(&'static str),
+    Unicode(char),
+    UnicodeMode(UnicodeMode),
+}
+
+impl KeyAction {
+    pub const fn key(code: KeyCode) -> KeyAction {
+        KeyAction::Key {
+            code,
+            modifiers: Modifiers::empty(),
+        }
+    }
+
+    /// Key sent with modifiers, such as Ctrl+C or Shift+1 for '!'
+    pub const fn shortcut(modifiers: Modifiers, code: KeyCode) -> KeyAction {
+        KeyAction::Key { code, modifiers }
+    }
+}
+
+impl Output {
+    pub(crate) fn press(code: KeyCode, modifiers: Modifiers) -> Output {
+        if modifiers.is_empty() {
+            Output::Press(code)
+        } else {
+            Output::PressWith(code, modifiers)
+        }
+    }
+
+    /// Change for the host from pressing or releasing an action, if it makes one
+    pub(crate) fn of(action: KeyAction, pressed: bool) -> Option<Output> {
+        Some(match action {
+            KeyAction::Key { code, modifiers } if pressed => Output::press(code, modifiers),
+            KeyAction::Key { code, .. } => Output::Release(code),
+            KeyAction::Consumer(usage) if pressed => Output::PressConsumer(usage),
+            KeyAction::Consumer(usage) => Output::ReleaseConsumer(usage),
+            KeyAction::System(usage) if pressed => Output::PressSystem(usage),
+            KeyAction::System(usage) => Output::ReleaseSystem(usage),
+            KeyAction::Mouse(action) if pressed => Output::PressMouse(action),
+            KeyAction::Mouse(action) => Output::ReleaseMouse(action),
+            KeyAction::Macro(steps) if pressed => Output::Macro(steps),
+            KeyAction::Unicode(c) if pressed => Output::Unicode(c),
+            KeyAction::UnicodeMode(mode) if pressed => Output::UnicodeMode(mode),
+            _ => return None,
+        })
+    }
+}
+
+const MAX_OUTPUTS: usize = 64;
+//key events and triggered presses wait while less of the queue than this is free, as when the
+//host stops taking reports, so there is always room for the outputs of one scan and for the
+//releases of everything already pressed. layouts drop what doesn't fit rather than panic
+const MIN_FREE_OUTPUTS: usize = 32;
+
+/// Changes waiting to be reported to the host, in order
+pub type Outputs = ArrayVec<Output, MAX_OUTPUTS>;
+
+pub trait KeyboardLayout<const N: usize> {
+    /// Turn a key press or release into changes for the host
+    fn event(&mut self, event: &KeyEvent, output: &mut Outputs);
+    /// Press or release an action that is not on a key, such as a combo's
+    fn trigger(&mut self, _action: KeyAction, _pressed: bool, _at: u32, _output: &mut Outputs) {}
+    /// Act on the passing of time, called after every scan with the same clock as the key events
+    fn tick(&mut self, _now: u32, _output: &mut Outputs) {}
+    /// Keys typed so far after a leader key, while a sequence is being typed
+    fn leader(&self) -> Option<&[KeyCode]> {
+        None
+    }
+    /// One-shot modifiers and layers that are on
+    fn one_shots(&self) -> OneShots {
+        OneShots::default()
+    }
+    /// Highest layer switched on
+    fn active_layer(&self) -> u8 {
+        0
+    }
+}
+
+pub struct BasicKeyboardLayout<const N: usize> {
+    keymap: [KeyAction; N],
+}
+
+impl<const N: usize> BasicKeyboardLayout<N> {
+    pub fn new(keymap: [KeyAction; N]) -> BasicKeyboardLayout<N> {
+        BasicKeyboardLayout { keymap }
+    }
+}
+
+impl<const N: usize> KeyboardLayout<N> for BasicKeyboardLayout<N> {
+    fn event(&mut self, event: &KeyEvent, output: &mut Outputs) {
+        self.trigger(self.keymap[event.key], event.pressed, event.at, output);
+    }
+
+    fn trigger(&mut self, action: KeyAction, pressed: bool, _at: u32, output: &mut Outputs) {
+        if let Some(change) = Output::of(action, pressed) {
+            output.try_push(change).ok();
+        }
+    }
+}
+
+pub struct KeyboardState<const KEY_COUNT: usize> {
+    pub modifiers: Modifiers,
+    pub keycodes: ArrayVec<KeyCode, MAX_HELD>,
+    pub keys: [KeyState; KEY_COUNT],
+    pub leader: Option<LeaderKeys>,
+    pub one_shots: OneShots,
+    /// Consumer usage last pressed and still held, or 0
+    pub consumer: u16,
+    /// System control usage last pressed and still held, or 0
+    pub system: u8,
+}
+
+/// Most key codes held at once, counting a code held by two keys twice
+pub const MAX_HELD: usize = 32;
+
+pub struct Keyboard<KM, KL, const KEY_COUNT: usize> {
+    matrix: KM,
+    layout: KL,
+    outputs: Outputs,
+    //macro being played, ahead of the rest of the queue
+    player: Option<MacroPlayer>,
+    held: ArrayVec<KeyCode, MAX_HELD>,
+    //modifiers bundled with the last key pressed, until another key is pressed or it is released
+    weak: Option<(KeyCode, Modifiers)>,
+    //consumer and system usages last pressed, their reports only have room for one and an
+    //earlier usage still held isn't reported again, as that would be a second press of it
+    consumer: u16,
+    system: u8,
+    mouse: Mouse,
+    //actions pressed with `trigger` and queued, the only ones whose releases are queued
+    triggered: ArrayVec<KeyAction, MAX_HELD>,
+    now: u32,
+    //reports wait for a delay until this time
+    resume_at: Option<u32>,
+    host_layout: HostLayout,
+    unicode_mode: UnicodeMode,
+}
+
+impl<KM, KL, const KEY_COUNT: usize> Keyboard<KM, KL, KEY_COUNT>
+where
+    KM: KeyboardMatrix<KEY_COUNT>,
+    KL: KeyboardLayout<KEY_COUNT>,
+{
+    pub fn new(matrix: KM, layout: KL) -> Keyboard<KM, KL, KEY_COUNT> {
+        Keyboard {
+            matrix,
+            layout,
+            outputs: Outputs::new(),
+            player: None,
+            held: ArrayVec::new(),
+            weak: None,
+            consumer: 0,
+            system: 0,
+            mouse: Mouse::new(),
+            triggered: ArrayVec::new(),
+            now: 0,
+            resume_at: None,
+            host_layout: HostLayout::default(),
+            unicode_mode: UnicodeMode::default(),
+        }
+    }
+    /// Scan the keys at a timestamp in ms from a free running, wrapping clock
+    pub fn update(&mut self, now: u32) -> Result<KeyEvents<KEY_COUNT>, KM::Error> {
+        self.now = now;
+        if self.outputs.remaining_capacity() < MIN_FREE_OUTPUTS {
+            return Ok(KeyEvents::new());
+        }
+        let events = self.matrix.update(now)?;
+        for event in &events {
+            self.layout.event(event, &mut self.outputs);
+        }
+        self.layout.tick(now, &mut self.outputs);
+        Ok(events)
+    }
+    pub fn matrix(&self) -> &KM {
+        &self.matrix
+    }
+    pub fn layout(&self) -> &KL {
+        &self.layout
+    }
+    /// Press or release an action that is not on a key, such as a turn of an encoder
+    pub fn trigger(&mut self, action: KeyAction, pressed: bool) {
+        if pressed {
+            if self.outputs.remaining_capacity() < MIN_FREE_OUTPUTS
+                || self.triggered.try_push(action).is_err()
+            {
+                return;
+            }
+        } else {
+            //a release without a queued press would be left over in the queue, and one with
+            //a press always has room, so nothing pressed is left held
+            match self.triggered.iter().position(|&a| a == action) {
+                Some(i) => self.triggered.remove(i),
+                None => return,
+            };
+        }
+        self.layout
+            .trigger(action, pressed, self.now, &mut self.outputs);
+    }
+    /// Keys as last reported, without applying queued changes
+    pub fn state(&self) -> Result<KeyboardState<KEY_COUNT>, KM::Error> {
+        let mut modifiers = self.weak.map_or(Modifiers::empty(), |(_, m)| m);
+        let mut keycodes = ArrayVec::new();
+
+        for &code in &self.held {
+            if code.is_modifier() {
+                modifiers |= Modifiers::from(code);
+            } else if !keycodes.contains(&code) {
+                //report rollover is left to the report format
+                keycodes.try_push(code).ok();
+            }
+        }
+
+        Ok(KeyboardState {
+            modifiers,
+            keycodes,
+            keys: self.matrix.keys()?,
+            leader: self
+                .layout
+                .leader()
+                .map(|keys| keys.iter().copied().take(MAX_LEADER_KEYS).collect()),
+            one_shots: self.layout.one_shots(),
+            consumer: self.consumer,
+            system: self.system,
+        })
+    }
+    pub fn host_layout(&self) -> HostLayout {
+        self.host_layout
+    }
+    /// Set the keyboard layout the host uses, for typing text
+    pub fn set_host_layout(&mut self, host_layout: HostLayout) {
+        self.host_layout = host_layout;
+    }
+    pub fn unicode_mode(&self) -> UnicodeMode {
+        self.unicode_mode
+    }
+    /// Set the input method the host uses to type unicode characters by their code
+    pub fn set_unicode_mode(&mut self, unicode_mode: UnicodeMode) {
+        self.unicode_mode = unicode_mode;
+    }
+    /// Set how held mouse keys speed up the pointer and the wheel
+    pub fn set_mouse_acceleration(&mut self, pointer: Acceleration, wheel: Acceleration) {
+        self.mouse.pointer = pointer;
+        self.mouse.wheel = wheel;
+    }
+    /// Mouse movement since the last call, meant to be polled on every scan
+    pub fn mouse_report(&mut self) -> Option<MouseReport> {
+        self.mouse.report(self.now)
+    }
+    /// Queue text to be typed, failing without typing any of it if the host layout can't
+    pub fn type_text(&mut self, text: &'static str) -> Result<(), TextError> {
+        self.host_layout.check(text)?;
+        self.outputs
+            .try_push(Output::Text(text))
+            .map_err(|_| TextError::QueueFull)
+    }
+    /// Whether `next_state` has changes to report now
+    pub fn has_report(&self) -> bool {
+        let waiting = self
+            .resume_at
+            .is_some_and(|at| (self.now.wrapping_sub(at) as i32) < 0);
+
+        !waiting && (self.player.is_some() || !self.outputs.is_empty())
+    }
+    /// Apply queued changes up to the first one that needs a report of its own, so a tap
+    /// reaches the host as a press followed by a release
+    pub fn next_state(&mut self) -> Result<KeyboardState<KEY_COUNT>, KM::Error> {
+        if !self.has_report() {
+            return self.state();
+        }
+        self.resume_at = None;
+
+        let mut changed = ArrayVec::<KeyCode, MAX_HELD>::new();
+        //a key's bundled modifiers get a report of their own, so they mask no other key
+        let mut bundled = false;
+        let mut pressed_any = false;
+        let mut consumer_changed = false;
+        let mut system_changed = false;
+        let mut mouse_changed = false;
+
+        while let Some(output) = self.peek_output() {
+            match output {
+                Output::Press(code) | Output::PressWith(code, _) | Output::Release(code) => {
+                    let pressed = !matches!(output, Output::Release(_));
+                    let with = matches!(output, Output::PressWith(..));
+                    if changed.contains(&code)
+                        || (bundled && pressed)
+                        || (with && pressed_any)
+                        || changed.try_push(code).is_err()
+                    {
+                        break;
+                    }
+                    bundled |= with;
+                    pressed_any |= pressed;
+                    self.apply(output);
+                }
+                Output::PressConsumer(_) | Output::ReleaseConsumer(_) => {
+                    //one consumer change per report
+                    if consumer_changed {
+                        break;
+                    }
+                    consumer_changed = true;
+                    self.apply(output);
+                }
+                Output::PressSystem(_) | Output::ReleaseSystem(_) => {
+                    if system_changed {
+                        break;
+                    }
+                    system_changed = true;
+                    self.apply(output);
+                }
+                Output::PressMouse(_) | Output::ReleaseMouse(_) => {
+                    //a click needs the press and release in separate mouse reports
+                    if mouse_changed {
+                        break;
+                    }
+                    mouse_changed = true;
+                    self.apply(output);
+                }
+                Output::Delay(ms) => {
+                    //report the changes before the delay first
+                    if changed.is_empty() {
+                        self.resume_at = Some(self.now.wrapping_add(ms));
+                        self.pop_output();
+                    }
+                    break;
+                }
+                Output::Macro(steps) => {
+                    self.pop_output();
+                    self.player = Some(MacroPlayer::new(steps, self.host_layout));
+                    continue;
+                }
+                Output::Text(text) => {
+                    self.pop_output();
+                    self.player = Some(MacroPlayer::text(text, self.host_layout));
+                    continue;
+                }
+                Output::Unicode(c) => {
+                    self.pop_output();
+                    self.player =
+                        Some(MacroPlayer::unicode(c, self.unicode_mode, self.host_layout));
+                    continue;
+                }
+                //in order with the changes around it
+                Output::UnicodeMode(mode) => self.unicode_mode = mode,
+            }
+            self.pop_output();
+        }
+
+        self.state()
+    }
+
+    fn peek_output(&mut self) -> Option<Output> {
+        if let Some(player) = &mut self.player {
+            match player.peek() {
+                Some(output) => return Some(output),
+                None => self.player = None,
+            }
+        }
+        self.outputs.first().copied()
+    }
+
+    fn pop_output(&mut self) {
+        match &mut self.player {
+            Some(player) => player.pop(),
+            None => {
+                if !self.outputs.is_empty() {
+                    self.outputs.remove(0);
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, output: Output) {
+        match output {
+            Output::Press(code) => {
+                self.held.try_push(code).ok();
+                if !code.is_modifier() {
+                    self.weak = None;
+                }
+            }
+            Output::PressWith(code, modifiers) => {
+                self.held.try_push(code).ok();
+                self.weak = Some((code, modifiers));
+            }
+            Output::Release(code) => {
+                if let Some(i) = self.held.iter().position(|&c| c == code) {
+                    self.held.remove(i);
+                }
+                if self.weak.is_some_and(|(weak, _)| weak == code) {
+                    self.weak = None;
+                }
+            }
+            Output::PressConsumer(usage) => self.consumer = usage,
+            Output::ReleaseConsumer(usage) => {
+                if self.consumer == usage {
+                    self.consumer = 0;
+                }
+            }
+            Output::PressSystem(usage) => self.system = usage,
+            Output::ReleaseSystem(usage) => {
+                if self.system == usage {
+                    self.system = 0;
+                }
+            }
+            Output::PressMouse(action) => self.mouse.press(action, self.now),
+            Output::ReleaseMouse(action) => self.mouse.release(action),
+            Output::Delay(_)
+            | Output::Macro(_)
+            | Output::Text(_)
+            | Output::Unicode(_)
+            | Output::UnicodeMode(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,108 @@
+use crate::keycode::KeyCode;
+use crate::text::{HostLayout, Stroke};
+use crate::unicode::{self, UnicodeMode};
+use crate::Output;
+use arrayvec::ArrayVec;
+
+/// Outputs for one step, long enough for a unicode character
+pub(crate) type Sequence = ArrayVec<Output, 40>;
+
+pub(crate) fn tap(out: &mut Sequence, code: KeyCode) {
+    out.push(Output::Press(code));
+    out.push(Output::Release(code));
+}
+
+/// Type a character's key with its modifiers, and a space after a dead key
+pub(crate) fn stroke(out: &mut Sequence, stroke: Stroke) {
+    out.extend(stroke.modifiers.keycodes().map(Output::Press));
+    tap(out, stroke.code);
+    out.extend(stroke.modifiers.keycodes().map(Output::Release));
+    if stroke.dead {
+        tap(out, KeyCode::Spacebar);
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MacroStep {
+    Press(KeyCode),
+    Release(KeyCode),
+    /// Press then release
+    Tap(KeyCode),
+    /// Wait in ms before the next step
+    Delay(u32),
+    /// Type text with the keyboard's host layout, skipping characters it can't type
+    Text(&'static str),
+}
+
+/// Plays a macro one output at a time, so long text needs no room in the output queue
+pub(crate) struct MacroPlayer {
+    layout: HostLayout,
+    steps: &'static [MacroStep],
+    //characters left to type of the current text step
+    text: core::str::Chars<'static>,
+    queued: Sequence,
+}
+
+impl MacroPlayer {
+    pub(crate) fn new(steps: &'static [MacroStep], layout: HostLayout) -> MacroPlayer {
+        MacroPlayer {
+            layout,
+            steps,
+            text: "".chars(),
+            queued: Sequence::new(),
+        }
+    }
+
+    pub(crate) fn text(text: &'static str, layout: HostLayout) -> MacroPlayer {
+        MacroPlayer {
+            text: text.chars(),
+            ..MacroPlayer::new(&[], layout)
+        }
+    }
+
+    pub(crate) fn unicode(c: char, mode: UnicodeMode, layout: HostLayout) -> MacroPlayer {
+        MacroPlayer {
+            queued: unicode::sequence(c, mode, layout),
+            ..MacroPlayer::new(&[], layout)
+        }
+    }
+
+    /// Next output, without taking it
+    pub(crate) fn peek(&mut self) -> Option<Output> {
+        while self.queued.is_empty() {
+            if !self.fill() {
+                return None;
+            }
+        }
+        self.queued.first().copied()
+    }
+
+    pub(crate) fn pop(&mut self) {
+        if !self.queued.is_empty() {
+            self.queued.remove(0);
+        }
+    }
+
+    fn fill(&mut self) -> bool {
+        if let Some(c) = self.text.next() {
+            if let Ok(s) = self.layout.stroke(c) {
+                stroke(&mut self.queued, s);
+            }
+            return true;
+        }
+
+        let Some((step, steps)) = self.steps.split_first() else {
+            return false;
+        };
+        self.steps = steps;
+
+        match *step {
+            MacroStep::Press(code) => self.queued.push(Output::Press(code)),
+            MacroStep::Release(code) => self.queued.push(Output::Release(code)),
+            MacroStep::Tap(code) => tap(&mut self.queued, code),
+            MacroStep::Delay(ms) => self.queued.push(Output::Delay(ms)),
+            MacroStep::Text(text) => self.text = text.chars(),
+        }
+        true
+    }
+}
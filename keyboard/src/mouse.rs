@@ -0,0 +1,182 @@
+use arrayvec::ArrayVec;
+
+pub const BUTTON_LEFT: u8 = 0x01;
+pub const BUTTON_RIGHT: u8 = 0x02;
+pub const BUTTON_MIDDLE: u8 = 0x04;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MouseAction {
+    /// Hold buttons, a mask of `BUTTON_LEFT`, `BUTTON_RIGHT` and `BUTTON_MIDDLE`
+    Button(u8),
+    Up,
+    Down,
+    Left,
+    Right,
+    WheelUp,
+    WheelDown,
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Curve {
+    Linear,
+    /// Slow at first for fine positioning
+    #[default]
+    Quadratic,
+}
+
+/// Speed of a held mouse key, from `initial` to `max` in units per second over `time_to_max` ms
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Acceleration {
+    pub initial: u32,
+    pub max: u32,
+    pub time_to_max: u32,
+    pub curve: Curve,
+}
+
+impl Acceleration {
+    pub const POINTER: Acceleration = Acceleration {
+        initial: 100,
+        max: 1200,
+        time_to_max: 1000,
+        curve: Curve::Quadratic,
+    };
+    pub const WHEEL: Acceleration = Acceleration {
+        initial: 8,
+        max: 40,
+        time_to_max: 1000,
+        curve: Curve::Linear,
+    };
+
+    /// Units per second after a key has been held for `held` ms
+    pub fn speed(&self, held: u32) -> u32 {
+        if held >= self.time_to_max || self.max <= self.initial {
+            return self.max.max(self.initial);
+        }
+
+        let range = (self.max - self.initial) as u64;
+        let (held, time) = (held as u64, self.time_to_max as u64);
+        let gained = match self.curve {
+            Curve::Linear => range * held / time,
+            Curve::Quadratic => range * held * held / (time * time),
+        };
+        self.initial + gained as u32
+    }
+}
+
+/// Change since the last report, with the buttons held
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct MouseReport {
+    pub buttons: u8,
+    pub x: i8,
+    pub y: i8,
+    /// Positive scrolls up
+    pub wheel: i8,
+}
+
+//movement longer than this between reports is not caught up on
+const MAX_STEP: u32 = 50;
+
+pub(crate) struct Mouse {
+    pub(crate) pointer: Acceleration,
+    pub(crate) wheel: Acceleration,
+    buttons: ArrayVec<u8, 8>,
+    //movement keys held and when they were pressed
+    moving: ArrayVec<(MouseAction, u32), 8>,
+    //distance not yet reported, in thousandths of a unit for x, y and wheel
+    remainder: [i32; 3],
+    reported_buttons: u8,
+    reported_at: u32,
+}
+
+impl Mouse {
+    pub(crate) fn new() -> Mouse {
+        Mouse {
+            pointer: Acceleration::POINTER,
+            wheel: Acceleration::WHEEL,
+            buttons: ArrayVec::new(),
+            moving: ArrayVec::new(),
+            remainder: [0; 3],
+            reported_buttons: 0,
+            reported_at: 0,
+        }
+    }
+
+    pub(crate) fn buttons(&self) -> u8 {
+        self.buttons.iter().fold(0, |mask, &b| mask | b)
+    }
+
+    //axis of x, y or wheel and the direction a movement key moves along it
+    fn axis(&self, action: MouseAction) -> Option<(usize, i32, Acceleration)> {
+        match action {
+            MouseAction::Left => Some((0, -1, self.pointer)),
+            MouseAction::Right => Some((0, 1, self.pointer)),
+            MouseAction::Up => Some((1, -1, self.pointer)),
+            MouseAction::Down => Some((1, 1, self.pointer)),
+            MouseAction::WheelUp => Some((2, 1, self.wheel)),
+            MouseAction::WheelDown => Some((2, -1, self.wheel)),
+            MouseAction::Button(_) => None,
+        }
+    }
+
+    pub(crate) fn press(&mut self, action: MouseAction, now: u32) {
+        if let MouseAction::Button(mask) = action {
+            self.buttons.try_push(mask).ok();
+        } else if let Some((axis, sign, _)) = self.axis(action) {
+            if self.moving.is_empty() {
+                self.reported_at = now;
+            }
+            if self.moving.try_push((action, now)).is_ok() {
+                //a tap moves one unit
+                self.remainder[axis] += sign * 1000;
+            }
+        }
+    }
+
+    pub(crate) fn release(&mut self, action: MouseAction) {
+        if let MouseAction::Button(mask) = action {
+            if let Some(i) = self.buttons.iter().position(|&b| b == mask) {
+                self.buttons.remove(i);
+            }
+        } else if let Some(i) = self.moving.iter().position(|&(a, _)| a == action) {
+            self.moving.remove(i);
+            if self.moving.is_empty() {
+                //drop part units, whole ones are still to be reported
+                self.remainder.iter_mut().for_each(|r| *r -= *r % 1000);
+            }
+        }
+    }
+
+    /// Movement since the last report, or `None` when there is nothing to send
+    pub(crate) fn report(&mut self, now: u32) -> Option<MouseReport> {
+        let elapsed = now.wrapping_sub(self.reported_at).min(MAX_STEP) as i32;
+        self.reported_at = now;
+
+        for &(action, since) in &self.moving {
+            if let Some((axis, sign, acceleration)) = self.axis(action) {
+                //units per second over ms gives thousandths of a unit
+                let speed = acceleration.speed(now.wrapping_sub(since)) as i32;
+                self.remainder[axis] += sign * speed * elapsed;
+            }
+        }
+
+        let mut step = [0i8; 3];
+        for (step, remainder) in step.iter_mut().zip(&mut self.remainder) {
+            let whole = (*remainder / 1000).clamp(i8::MIN as i32, i8::MAX as i32);
+            *remainder -= whole * 1000;
+            *step = whole as i8;
+        }
+
+        let buttons = self.buttons();
+        if step == [0; 3] && buttons == self.reported_buttons {
+            return None;
+        }
+        self.reported_buttons = buttons;
+
+        Some(MouseReport {
+            buttons,
+            x: step[0],
+            y: step[1],
+            wheel: step[2],
+        })
+    }
+}
@@ -0,0 +1,18 @@
+use crate::keycode::Modifiers;
+
+/// Modifiers and layers waiting for the next key, and those locked on by a double tap
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct OneShots {
+    pub modifiers: Modifiers,
+    pub locked_modifiers: Modifiers,
+    /// Bit per layer
+    pub layers: u32,
+    /// Bit per layer
+    pub locked_layers: u32,
+}
+
+impl OneShots {
+    pub fn is_empty(&self) -> bool {
+        *self == OneShots::default()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::keycode::KeyCode;
+use crate::KeyboardState;
+
+pub const BOOT_REPORT_LEN: usize = 8;
+/// Modifiers then a bit for each usage up to `NKRO_MAX_USAGE`
+pub const NKRO_REPORT_LEN: usize = 1 + (NKRO_MAX_USAGE as usize + 1) / 8;
+/// Highest keyboard page usage in the NKRO bitmap, higher key codes are left out
+pub const NKRO_MAX_USAGE: u8 = 0xDF;
+
+/// Report descriptor for the NKRO bitmap report, with the boot keyboard's LED output
+#[rustfmt::skip]
+pub const NKRO_DESCRIPTOR: [u8; 47] = [
+    0x05, 0x01,         //usage page (generic desktop)
+    0x09, 0x06,         //usage (keyboard)
+    0xA1, 0x01,         //collection (application)
+    0x05, 0x07,         //  usage page (keyboard)
+    0x19, 0xE0,         //  usage minimum (left control)
+    0x29, 0xE7,         //  usage maximum (right gui)
+    0x15, 0x00,         //  logical minimum (0)
+    0x25, 0x01,         //  logical maximum (1)
+    0x75, 0x01,         //  report size (1)
+    0x95, 0x08,         //  report count (8)
+    0x81, 0x02,         //  input (data, variable, absolute), modifiers
+    0x05, 0x08,         //  usage page (leds)
+    0x19, 0x01,         //  usage minimum (num lock)
+    0x29, 0x05,         //  usage maximum (kana)
+    0x95, 0x05,         //  report count (5)
+    0x91, 0x02,         //  output (data, variable, absolute), leds
+    0x95, 0x03,         //  report count (3)
+    0x91, 0x01,         //  output (constant), padding
+    0x05, 0x07,         //  usage page (keyboard)
+    0x19, 0x00,         //  usage minimum (0)
+    0x29, NKRO_MAX_USAGE, //  usage maximum
+    0x95, NKRO_MAX_USAGE + 1, //  report count, a bit per usage
+    0x81, 0x02,         //  input (data, variable, absolute), key bitmap
+    0xC0,               //end collection
+];
+
+impl<const KEY_COUNT: usize> KeyboardState<KEY_COUNT> {
+    /// 6KRO boot keyboard report, with every slot ErrorRollOver when more keys are held
+    pub fn boot_report(&self) -> [u8; BOOT_REPORT_LEN] {
+        let mut report = [0; BOOT_REPORT_LEN];
+        report[0] = self.modifiers.bits();
+
+        let slots = &mut report[2..];
+        if self.keycodes.len() > slots.len() {
+            slots.fill(KeyCode::ErrorRollOver as u8);
+        } else {
+            for (slot, &code) in slots.iter_mut().zip(&self.keycodes) {
+                *slot = code as u8;
+            }
+        }
+        report
+    }
+
+    /// Bitmap report of every key held, for `NKRO_DESCRIPTOR`
+    pub fn nkro_report(&self) -> [u8; NKRO_REPORT_LEN] {
+        let mut report = [0; NKRO_REPORT_LEN];
+        report[0] = self.modifiers.bits();
+
+        for &code in &self.keycodes {
+            let usage = code as u8;
+            if usage <= NKRO_MAX_USAGE {
+                report[1 + usage as usize / 8] |= 1 << (usage % 8);
+            }
+        }
+        report
+    }
+}
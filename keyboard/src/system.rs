@@ -0,0 +1,21 @@
+//Usages for KeyAction::System, values taken from USB HID Usage Tables - https://www.usb.org/sites/default/files/documents/hut1_12v2.pdf p31 Generic Desktop Page (0x01)
+
+pub const POWER_DOWN: u8 = 0x81;
+pub const SLEEP: u8 = 0x82;
+pub const WAKE_UP: u8 = 0x83;
+
+/// Report descriptor for a one byte report holding the system control usage pressed, or 0
+#[rustfmt::skip]
+pub const SYSTEM_CONTROL_DESCRIPTOR: [u8; 23] = [
+    0x05, 0x01,         //usage page (generic desktop)
+    0x09, 0x80,         //usage (system control)
+    0xA1, 0x01,         //collection (application)
+    0x19, POWER_DOWN,   //  usage minimum
+    0x29, WAKE_UP,      //  usage maximum
+    0x16, POWER_DOWN, 0x00, //  logical minimum, two bytes so it is not negative
+    0x26, WAKE_UP, 0x00,    //  logical maximum
+    0x75, 0x08,         //  report size (8)
+    0x95, 0x01,         //  report count (1)
+    0x81, 0x00,         //  input (data, array, absolute), out of range values mean none
+    0xC0,               //end collection
+];
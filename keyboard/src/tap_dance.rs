@@ -0,0 +1,61 @@
+use crate::KeyAction;
+
+/// Actions picked by the number of taps in a row, and by holding the last press
+#[derive(Debug, Eq, PartialEq)]
+pub struct TapDance {
+    /// Actions for one tap, two taps and so on, the dance ends on the last so more taps start
+    /// a new one
+    pub taps: &'static [KeyAction],
+    /// Held when the first press is held, or `NoOp` to hold the single tap action
+    pub hold: KeyAction,
+    /// Held when a press after a tap is held, or `NoOp` to hold the tap action
+    pub tap_hold: KeyAction,
+    /// Time in ms to the next tap, and to hold a press
+    pub term: u32,
+}
+
+impl TapDance {
+    pub const DEFAULT_TERM: u32 = 200;
+
+    pub const fn new(taps: &'static [KeyAction]) -> TapDance {
+        TapDance {
+            taps,
+            hold: KeyAction::NoOp,
+            tap_hold: KeyAction::NoOp,
+            term: TapDance::DEFAULT_TERM,
+        }
+    }
+
+    pub const fn hold(self, hold: KeyAction) -> TapDance {
+        TapDance { hold, ..self }
+    }
+
+    pub const fn tap_hold(self, tap_hold: KeyAction) -> TapDance {
+        TapDance { tap_hold, ..self }
+    }
+
+    pub const fn term(self, term: u32) -> TapDance {
+        TapDance { term, ..self }
+    }
+
+    pub(crate) fn tap(&self, count: u8) -> KeyAction {
+        match self.taps.get(count.saturating_sub(1) as usize) {
+            Some(&action) => action,
+            None => self.taps.last().copied().unwrap_or(KeyAction::NoOp),
+        }
+    }
+
+    pub(crate) fn held(&self, count: u8) -> KeyAction {
+        match (count, self.hold, self.tap_hold) {
+            (1, KeyAction::NoOp, _) => self.tap(count),
+            (1, hold, _) => hold,
+            (_, _, KeyAction::NoOp) => self.tap(count),
+            (_, _, tap_hold) => tap_hold,
+        }
+    }
+
+    //no later press can change the outcome
+    pub(crate) fn is_final(&self, count: u8) -> bool {
+        count as usize >= self.taps.len() && (count >= 2 || self.tap_hold == KeyAction::NoOp)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::KeyAction;
+
+/// Tap and hold actions for one key, decided when the key is released, held for the tapping
+/// term, or by the keys pressed while it is down
+#[derive(Debug, Eq, PartialEq)]
+pub struct TapHold {
+    pub tap: KeyAction,
+    pub hold: KeyAction,
+    /// Time in ms after which a press is a hold
+    pub tapping_term: u32,
+    /// Hold as soon as another key is pressed
+    pub hold_on_other_key_press: bool,
+    /// Hold when another key is pressed and released before this key is released
+    pub permissive_hold: bool,
+    /// Tap on release after a hold with no other key pressed
+    pub retro_tapping: bool,
+}
+
+impl TapHold {
+    pub const DEFAULT_TAPPING_TERM: u32 = 200;
+
+    pub const fn new(tap: KeyAction, hold: KeyAction) -> TapHold {
+        TapHold {
+            tap,
+            hold,
+            tapping_term: TapHold::DEFAULT_TAPPING_TERM,
+            hold_on_other_key_press: false,
+            permissive_hold: false,
+            retro_tapping: false,
+        }
+    }
+
+    pub const fn tapping_term(self, tapping_term: u32) -> TapHold {
+        TapHold {
+            tapping_term,
+            ..self
+        }
+    }
+
+    pub const fn hold_on_other_key_press(self) -> TapHold {
+        TapHold {
+            hold_on_other_key_press: true,
+            ..self
+        }
+    }
+
+    pub const fn permissive_hold(self) -> TapHold {
+        TapHold {
+            permissive_hold: true,
+            ..self
+        }
+    }
+
+    pub const fn retro_tapping(self) -> TapHold {
+        TapHold {
+            retro_tapping: true,
+            ..self
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use super::{key, TestLayout};
+use crate::keycode::{KeyCode, Modifiers};
+use crate::{
+    BasicKeyboardLayout, Combo, ComboLayout, ComboOverlap, KeyAction, KeyboardLayout,
+    LayeredKeyboardLayout, Output, TapHold,
+};
+
+//keys 0 to 2 take part in combos, key 3 does not
+const KEYMAP: [KeyAction; 4] = [
+    key(KeyCode::A),
+    key(KeyCode::B),
+    key(KeyCode::C),
+    key(KeyCode::D),
+];
+
+static PAIRS: [Combo; 2] = [
+    Combo::new(&[0, 1], key(KeyCode::X)),
+    Combo::new(&[1, 2], key(KeyCode::Z)),
+];
+
+static NESTED: [Combo; 2] = [
+    Combo::new(&[0, 1], key(KeyCode::X)),
+    Combo::new(&[0, 1, 2], key(KeyCode::Y)).term(80),
+];
+
+#[test]
+fn other_keys_not_delayed() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS));
+
+    layout.press(3, 0);
+    assert_eq!(layout.held(), &[KeyCode::D]);
+    layout.release(3, 10);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn combo_fires_in_place_of_keys() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS));
+
+    layout.press(0, 0);
+    assert!(layout.held().is_empty());
+    layout.press(1, 20);
+    assert_eq!(layout.held(), &[KeyCode::X]);
+
+    //the first key up releases the combo, the second is swallowed
+    layout.release(1, 100);
+    assert!(layout.held().is_empty());
+    layout.release(0, 110);
+    assert_eq!(
+        layout.take_outputs(),
+        [Output::Press(KeyCode::X), Output::Release(KeyCode::X)]
+    );
+
+    //in either order
+    layout.press(2, 200);
+    layout.press(1, 210);
+    assert_eq!(layout.held(), &[KeyCode::Z]);
+}
+
+#[test]
+fn combo_key_tapped_alone() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS));
+
+    layout.press(0, 0);
+    layout.release(0, 30);
+    assert_eq!(
+        layout.take_outputs(),
+        [Output::Press(KeyCode::A), Output::Release(KeyCode::A)]
+    );
+}
+
+#[test]
+fn combo_key_held_past_timeout() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS));
+
+    layout.press(0, 0);
+    layout.tick(49);
+    assert!(layout.held().is_empty());
+    layout.tick(50);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+
+    //too late to make a combo
+    layout.press(1, 60);
+    layout.tick(110);
+    assert_eq!(layout.held(), &[KeyCode::A, KeyCode::B]);
+}
+
+#[test]
+fn timeout() {
+    let mut layout =
+        TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS).timeout(10));
+
+    layout.press(0, 0);
+    layout.press(1, 10);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+}
+
+#[test]
+fn interrupted_by_other_key() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS));
+
+    layout.press(0, 0);
+    layout.press(3, 5);
+    assert_eq!(
+        layout.take_outputs(),
+        [Output::Press(KeyCode::A), Output::Press(KeyCode::D)]
+    );
+}
+
+#[test]
+fn keys_past_combo_mask() {
+    let mut keymap = [key(KeyCode::D); 40];
+    keymap[0] = key(KeyCode::A);
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(keymap), &PAIRS));
+
+    layout.press(35, 0);
+    layout.release(35, 10);
+    layout.press(0, 20);
+    layout.press(35, 25);
+    layout.release(35, 30);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::D),
+            Output::Release(KeyCode::D),
+            Output::Press(KeyCode::A),
+            Output::Press(KeyCode::D),
+            Output::Release(KeyCode::D),
+        ]
+    );
+}
+
+#[test]
+fn releases_fill_buffer() {
+    let mut keymap = [key(KeyCode::D); 64];
+    keymap[0] = key(KeyCode::A);
+    keymap[1] = key(KeyCode::B);
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(keymap), &PAIRS));
+
+    for key in 32..64 {
+        layout.press(key, 0);
+    }
+    layout.press(0, 10);
+    for key in 32..63 {
+        layout.release(key, 20);
+    }
+    //no room left to buffer the press, so the keys before it are settled first
+    layout.press(1, 30);
+    assert_eq!(layout.held(), &[KeyCode::D, KeyCode::A]);
+
+    layout.tick(80);
+    assert_eq!(layout.held(), &[KeyCode::D, KeyCode::A, KeyCode::B]);
+}
+
+#[test]
+fn keys_not_in_one_combo() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS));
+
+    //0 and 2 are both combo keys but there is no combo of them
+    layout.press(0, 0);
+    layout.press(2, 5);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+    layout.press(1, 10);
+    assert_eq!(layout.held(), &[KeyCode::A, KeyCode::Z]);
+}
+
+#[test]
+fn longest_overlap_waits() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &NESTED));
+
+    layout.press(0, 0);
+    layout.press(1, 10);
+    assert!(layout.held().is_empty());
+    layout.press(2, 20);
+    assert_eq!(layout.held(), &[KeyCode::Y]);
+}
+
+#[test]
+fn longest_overlap_falls_back() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &NESTED));
+
+    //the longer combo's term has passed
+    layout.press(0, 0);
+    layout.press(1, 10);
+    layout.tick(79);
+    assert!(layout.held().is_empty());
+    layout.tick(80);
+    assert_eq!(layout.held(), &[KeyCode::X]);
+
+    //released before the longer combo could complete
+    layout.release(0, 90);
+    layout.release(1, 100);
+    layout.press(0, 200);
+    layout.press(1, 210);
+    layout.release(1, 220);
+    assert_eq!(
+        layout.take_outputs()[2..],
+        [Output::Press(KeyCode::X), Output::Release(KeyCode::X)]
+    );
+}
+
+#[test]
+fn shortest_overlap_fires_first() {
+    let mut layout = TestLayout::new(
+        ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &NESTED).overlap(ComboOverlap::Shortest),
+    );
+
+    layout.press(0, 0);
+    layout.press(1, 10);
+    assert_eq!(layout.held(), &[KeyCode::X]);
+    layout.press(2, 20);
+    assert_eq!(layout.held(), &[KeyCode::X, KeyCode::C]);
+}
+
+#[test]
+fn combo_on_layers() {
+    static LAYER: [Combo; 1] = [Combo::new(&[0, 1], KeyAction::MomentaryLayer(1))];
+    let mut layout = TestLayout::new(ComboLayout::new(
+        LayeredKeyboardLayout::new([
+            KEYMAP,
+            [
+                KeyAction::Transparent,
+                KeyAction::Transparent,
+                key(KeyCode::Kp1),
+                key(KeyCode::Kp2),
+            ],
+        ]),
+        &LAYER,
+    ));
+
+    layout.press(0, 0);
+    layout.press(1, 5);
+    assert_eq!(layout.layout().active_layer(), 1);
+    layout.press(3, 10);
+    assert_eq!(layout.held(), &[KeyCode::Kp2]);
+
+    layout.release(0, 20);
+    assert_eq!(layout.layout().active_layer(), 0);
+}
+
+#[test]
+fn combo_only_on_its_layers() {
+    static KEYPAD: [Combo; 1] = [Combo::new(&[0, 1], key(KeyCode::X)).layers(1 << 0)];
+    let mut layout = TestLayout::new(ComboLayout::new(
+        LayeredKeyboardLayout::new([
+            [
+                key(KeyCode::A),
+                key(KeyCode::B),
+                key(KeyCode::C),
+                KeyAction::ToggleLayer(1),
+            ],
+            [
+                key(KeyCode::Kp1),
+                key(KeyCode::Kp2),
+                KeyAction::Transparent,
+                KeyAction::Transparent,
+            ],
+        ]),
+        &KEYPAD,
+    ));
+
+    layout.tap(3, 0);
+    //not held back on another layer
+    layout.press(0, 20);
+    assert_eq!(layout.held(), &[KeyCode::Kp1]);
+    layout.press(1, 25);
+    assert_eq!(layout.held(), &[KeyCode::Kp1, KeyCode::Kp2]);
+    layout.release(0, 30);
+    layout.release(1, 30);
+
+    layout.tap(3, 40);
+    layout.press(0, 60);
+    layout.press(1, 65);
+    assert_eq!(layout.held(), &[KeyCode::X]);
+}
+
+#[test]
+fn release_waits_for_buffered_presses() {
+    let mut layout = TestLayout::new(ComboLayout::new(BasicKeyboardLayout::new(KEYMAP), &PAIRS));
+
+    layout.press(3, 0);
+    layout.press(0, 10);
+    //key 3 is released after key 0 is pressed, whether or not a combo follows
+    layout.release(3, 20);
+    assert_eq!(layout.held(), &[KeyCode::D]);
+    layout.tick(60);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::D),
+            Output::Press(KeyCode::A),
+            Output::Release(KeyCode::D),
+        ]
+    );
+
+    layout.release(0, 70);
+    layout.press(3, 100);
+    layout.press(0, 110);
+    layout.release(3, 115);
+    layout.press(1, 120);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Release(KeyCode::A),
+            Output::Press(KeyCode::D),
+            Output::Press(KeyCode::X),
+            Output::Release(KeyCode::D),
+        ]
+    );
+}
+
+#[test]
+fn replayed_in_time_order() {
+    static ENTER_CTRL: TapHold = TapHold::new(key(KeyCode::KpEnter), key(KeyCode::LeftControl));
+    let mut layout = TestLayout::new(ComboLayout::new(
+        LayeredKeyboardLayout::new([[
+            key(KeyCode::A),
+            key(KeyCode::B),
+            key(KeyCode::C),
+            KeyAction::TapHold(&ENTER_CTRL),
+        ]]),
+        &PAIRS,
+    ));
+
+    //a tap-hold key released while a combo key is buffered is still a tap
+    layout.press(3, 0);
+    layout.press(0, 10);
+    layout.release(3, 20);
+    layout.tick(60);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::KpEnter),
+            Output::Press(KeyCode::A),
+            Output::Release(KeyCode::KpEnter),
+        ]
+    );
+}
+
+#[test]
+fn combo_uses_one_shot_modifier() {
+    let mut layout = TestLayout::new(ComboLayout::new(
+        LayeredKeyboardLayout::new([[
+            key(KeyCode::A),
+            key(KeyCode::B),
+            key(KeyCode::C),
+            KeyAction::OneShotMod(Modifiers::SHIFT_LEFT),
+        ]]),
+        &PAIRS,
+    ));
+
+    layout.tap(3, 0);
+    layout.press(1, 100);
+    layout.press(2, 105);
+    assert_eq!(layout.held(), &[KeyCode::LeftShift, KeyCode::Z]);
+    layout.release(1, 150);
+    layout.release(2, 150);
+    assert!(layout.held().is_empty());
+    assert!(layout.one_shots().is_empty());
+
+    layout.press(0, 200);
+    layout.tick(250);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+}
@@ -0,0 +1,59 @@
+use super::{basic_keyboard, key, keyboard, update};
+use crate::keycode::KeyCode;
+use crate::{consumer, KeyAction, LayeredKeyboardLayout};
+
+#[test]
+fn consumer_key_held() {
+    let mut keyboard = basic_keyboard([
+        KeyAction::Consumer(consumer::VOLUME_UP),
+        key(KeyCode::A),
+        KeyAction::NoOp,
+    ]);
+
+    update(&mut keyboard, 0, &[(0, true), (1, true)]);
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.consumer, consumer::VOLUME_UP);
+    assert_eq!(state.keycodes.as_slice(), &[KeyCode::A]);
+
+    update(&mut keyboard, 10, &[(0, false)]);
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.consumer, 0);
+    assert_eq!(state.keycodes.as_slice(), &[KeyCode::A]);
+}
+
+#[test]
+fn each_consumer_change_gets_a_report() {
+    let mut keyboard = keyboard(LayeredKeyboardLayout::new([[
+        KeyAction::Consumer(consumer::MUTE),
+        KeyAction::Consumer(consumer::PLAY_PAUSE),
+        KeyAction::NoOp,
+    ]]));
+
+    update(&mut keyboard, 0, &[(0, true), (1, true)]);
+    assert_eq!(keyboard.next_state().unwrap().consumer, consumer::MUTE);
+    assert_eq!(
+        keyboard.next_state().unwrap().consumer,
+        consumer::PLAY_PAUSE
+    );
+
+    //the earlier usage isn't sent again, that would mute a second time
+    update(&mut keyboard, 10, &[(1, false)]);
+    assert_eq!(keyboard.next_state().unwrap().consumer, 0);
+    update(&mut keyboard, 20, &[(0, false)]);
+    assert_eq!(keyboard.next_state().unwrap().consumer, 0);
+    assert!(!keyboard.has_report());
+}
+
+#[test]
+fn triggered_tap_spans_two_reports() {
+    let mut keyboard = basic_keyboard([KeyAction::NoOp; 3]);
+
+    let volume_down = KeyAction::Consumer(consumer::VOLUME_DOWN);
+    keyboard.trigger(volume_down, true);
+    keyboard.trigger(volume_down, false);
+    assert_eq!(
+        keyboard.next_state().unwrap().consumer,
+        consumer::VOLUME_DOWN
+    );
+    assert_eq!(keyboard.next_state().unwrap().consumer, 0);
+}
@@ -0,0 +1,110 @@
+extern crate std;
+
+use super::{basic_keyboard, key, keyboard, reports, update};
+use crate::keycode::{KeyCode, Modifiers};
+use crate::{KeyAction, LayeredKeyboardLayout, Output, TapHold};
+use std::vec::Vec;
+
+#[test]
+fn state_follows_keys() {
+    let mut keyboard = basic_keyboard([key(KeyCode::A), key(KeyCode::LeftShift), key(KeyCode::B)]);
+
+    update(&mut keyboard, 0, &[(0, true), (1, true), (2, true)]);
+    assert!(keyboard.state().unwrap().keycodes.is_empty());
+
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.modifiers, Modifiers::SHIFT_LEFT);
+    assert_eq!(state.keycodes.as_slice(), &[KeyCode::A, KeyCode::B]);
+
+    update(&mut keyboard, 1, &[(0, false)]);
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.keycodes.as_slice(), &[KeyCode::B]);
+}
+
+#[test]
+fn tap_spans_two_reports() {
+    static ENTER_CTRL: TapHold = TapHold::new(key(KeyCode::KpEnter), key(KeyCode::LeftControl));
+    let mut keyboard = keyboard(LayeredKeyboardLayout::new([[
+        KeyAction::TapHold(&ENTER_CTRL),
+        key(KeyCode::A),
+        KeyAction::NoOp,
+    ]]));
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    update(&mut keyboard, 10, &[(0, false)]);
+
+    assert_eq!(
+        keyboard.next_state().unwrap().keycodes.as_slice(),
+        &[KeyCode::KpEnter]
+    );
+    assert!(keyboard.next_state().unwrap().keycodes.is_empty());
+}
+
+#[test]
+fn held_by_two_keys() {
+    let mut keyboard = basic_keyboard([key(KeyCode::A), key(KeyCode::A), KeyAction::NoOp]);
+
+    update(&mut keyboard, 0, &[(0, true), (1, true)]);
+    assert_eq!(
+        keyboard.next_state().unwrap().keycodes.as_slice(),
+        &[KeyCode::A]
+    );
+    keyboard.next_state().unwrap();
+
+    update(&mut keyboard, 1, &[(0, false)]);
+    assert_eq!(
+        keyboard.next_state().unwrap().keycodes.as_slice(),
+        &[KeyCode::A]
+    );
+    update(&mut keyboard, 2, &[(1, false)]);
+    assert!(keyboard.next_state().unwrap().keycodes.is_empty());
+}
+
+#[test]
+fn full_queue_holds_back_events() {
+    let mut keyboard = basic_keyboard([key(KeyCode::A), key(KeyCode::B), KeyAction::NoOp]);
+
+    //a host that stops taking reports while an encoder keeps turning
+    for _ in 0..100 {
+        keyboard.trigger(key(KeyCode::C), true);
+        keyboard.trigger(key(KeyCode::C), false);
+    }
+    //a dropped press leaves no release behind
+    let count = |output| keyboard.outputs.iter().filter(|&&o| o == output).count();
+    assert_eq!(
+        count(Output::Press(KeyCode::C)),
+        count(Output::Release(KeyCode::C))
+    );
+    assert!(keyboard.outputs.remaining_capacity() >= crate::MIN_FREE_OUTPUTS - 2);
+
+    //key events wait in the matrix until there is room for them
+    update(&mut keyboard, 0, &[(0, true)]);
+    assert_eq!(keyboard.matrix().events.len(), 1);
+
+    let held: Vec<_> = reports(&mut keyboard, 1)
+        .into_iter()
+        .map(|(_, _, keycodes)| keycodes)
+        .collect();
+    assert!(held.iter().all(|keycodes| keycodes.len() <= 1));
+    assert_eq!(held.last().unwrap(), &[KeyCode::A]);
+}
+
+#[test]
+fn full_queue_while_held_releases() {
+    let mut keyboard = basic_keyboard([key(KeyCode::A), key(KeyCode::B), KeyAction::NoOp]);
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    keyboard.trigger(key(KeyCode::LeftShift), true);
+    for _ in 0..100 {
+        keyboard.trigger(key(KeyCode::C), true);
+        keyboard.trigger(key(KeyCode::C), false);
+    }
+    keyboard.trigger(key(KeyCode::LeftShift), false);
+    update(&mut keyboard, 1, &[(0, false)]);
+
+    //both releases are reported once the host takes reports again
+    let last = reports(&mut keyboard, 2).pop().unwrap();
+    assert_eq!(last.1, Modifiers::empty());
+    assert!(last.2.is_empty());
+    assert!(keyboard.held.is_empty());
+}
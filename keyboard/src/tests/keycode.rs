@@ -0,0 +1,280 @@
+use crate::keycode::{KeyCode, UnknownKeyCode};
+
+//Keyboard/Keypad Page (0x07) of HID Usage Tables 1.12, section 10
+const SPEC: &[(u8, KeyCode)] = &[
+    (0x00, KeyCode::None),           //Reserved (no event indicated)
+    (0x01, KeyCode::ErrorRollOver),  //ErrorRollOver
+    (0x02, KeyCode::POSTFail),       //POSTFail
+    (0x03, KeyCode::ErrorUndefined), //ErrorUndefined
+    (0x04, KeyCode::A),
+    (0x05, KeyCode::B),
+    (0x06, KeyCode::C),
+    (0x07, KeyCode::D),
+    (0x08, KeyCode::E),
+    (0x09, KeyCode::F),
+    (0x0A, KeyCode::G),
+    (0x0B, KeyCode::H),
+    (0x0C, KeyCode::I),
+    (0x0D, KeyCode::J),
+    (0x0E, KeyCode::K),
+    (0x0F, KeyCode::L),
+    (0x10, KeyCode::M),
+    (0x11, KeyCode::N),
+    (0x12, KeyCode::O),
+    (0x13, KeyCode::P),
+    (0x14, KeyCode::Q),
+    (0x15, KeyCode::R),
+    (0x16, KeyCode::S),
+    (0x17, KeyCode::T), //t and T
+    (0x18, KeyCode::U), //u and U
+    (0x19, KeyCode::V),
+    (0x1A, KeyCode::W),
+    (0x1B, KeyCode::X),
+    (0x1C, KeyCode::Y),
+    (0x1D, KeyCode::Z),
+    (0x1E, KeyCode::Kb1),
+    (0x1F, KeyCode::Kb2),
+    (0x20, KeyCode::Kb3),
+    (0x21, KeyCode::Kb4),
+    (0x22, KeyCode::Kb5),
+    (0x23, KeyCode::Kb6),
+    (0x24, KeyCode::Kb7),
+    (0x25, KeyCode::Kb8),
+    (0x26, KeyCode::Kb9),
+    (0x27, KeyCode::Kb0),
+    (0x28, KeyCode::Enter), //Return (ENTER)
+    (0x29, KeyCode::Escape),
+    (0x2A, KeyCode::Backspace),
+    (0x2B, KeyCode::Tab),
+    (0x2C, KeyCode::Spacebar), //Spacebar
+    (0x2D, KeyCode::Minus),
+    (0x2E, KeyCode::Equals),
+    (0x2F, KeyCode::LeftBracket),
+    (0x30, KeyCode::RightBracket),
+    (0x31, KeyCode::BackslashANSI),
+    (0x32, KeyCode::Hash), //Non-US # and ~
+    (0x33, KeyCode::Semicolon),
+    (0x34, KeyCode::Apostrophy),
+    (0x35, KeyCode::Grave),
+    (0x36, KeyCode::Comma),
+    (0x37, KeyCode::Dot),
+    (0x38, KeyCode::ForwardSlash),
+    (0x39, KeyCode::CapsLock),
+    (0x3A, KeyCode::F1),
+    (0x3B, KeyCode::F2),
+    (0x3C, KeyCode::F3),
+    (0x3D, KeyCode::F4),
+    (0x3E, KeyCode::F5),
+    (0x3F, KeyCode::F6),
+    (0x40, KeyCode::F7),
+    (0x41, KeyCode::F8),
+    (0x42, KeyCode::F9),
+    (0x43, KeyCode::F10),
+    (0x44, KeyCode::F11),
+    (0x45, KeyCode::F12),
+    (0x46, KeyCode::PrintScreen),
+    (0x47, KeyCode::ScrollLock),
+    (0x48, KeyCode::Pause),
+    (0x49, KeyCode::Insert),
+    (0x4A, KeyCode::Home),
+    (0x4B, KeyCode::PageUp),
+    (0x4C, KeyCode::Delete),
+    (0x4D, KeyCode::End),
+    (0x4E, KeyCode::PageDown),
+    (0x4F, KeyCode::RightArrow),
+    (0x50, KeyCode::LeftArrow),
+    (0x51, KeyCode::DownArrow),
+    (0x52, KeyCode::UpArrow),
+    (0x53, KeyCode::KpNumLock),
+    (0x54, KeyCode::KpBackslash), //Keypad /
+    (0x55, KeyCode::KpAsterisk),
+    (0x56, KeyCode::KpMinus),
+    (0x57, KeyCode::KpPlus),
+    (0x58, KeyCode::KpEnter),
+    (0x59, KeyCode::Kp1),
+    (0x5A, KeyCode::Kp2),
+    (0x5B, KeyCode::Kp3),
+    (0x5C, KeyCode::Kp4),
+    (0x5D, KeyCode::Kp5),
+    (0x5E, KeyCode::Kp6),
+    (0x5F, KeyCode::Kp7),
+    (0x60, KeyCode::Kp8),
+    (0x61, KeyCode::Kp9),
+    (0x62, KeyCode::Kp0),
+    (0x63, KeyCode::KpDot),        //Keypad . and Delete
+    (0x64, KeyCode::BackslashISO), //Non-US \ and |
+    (0x65, KeyCode::Application),
+    (0x66, KeyCode::Power),
+    (0x67, KeyCode::KpEquals), //Keypad =
+    (0x68, KeyCode::F13),
+    (0x69, KeyCode::F14),
+    (0x6A, KeyCode::F15),
+    (0x6B, KeyCode::F16),
+    (0x6C, KeyCode::F17),
+    (0x6D, KeyCode::F18),
+    (0x6E, KeyCode::F19),
+    (0x6F, KeyCode::F20),
+    (0x70, KeyCode::F21),
+    (0x71, KeyCode::F22),
+    (0x72, KeyCode::F23),
+    (0x73, KeyCode::F24),
+    (0x74, KeyCode::Execute),
+    (0x75, KeyCode::Help),
+    (0x76, KeyCode::Menu),
+    (0x77, KeyCode::Select),
+    (0x78, KeyCode::Stop),
+    (0x79, KeyCode::Again),
+    (0x7A, KeyCode::Undo),
+    (0x7B, KeyCode::Cut),
+    (0x7C, KeyCode::Copy),
+    (0x7D, KeyCode::Paste),
+    (0x7E, KeyCode::Find),
+    (0x7F, KeyCode::Mute),
+    (0x80, KeyCode::VolumeUp),
+    (0x81, KeyCode::VolumeDown),
+    (0x82, KeyCode::LockingCapsLock),
+    (0x83, KeyCode::LockingNum),
+    (0x84, KeyCode::LockingScrollLock),
+    (0x85, KeyCode::KpComma),
+    (0x86, KeyCode::KpEqualSign), //Keypad Equal Sign
+    (0x87, KeyCode::International1),
+    (0x88, KeyCode::International2),
+    (0x89, KeyCode::International3),
+    (0x8A, KeyCode::International4),
+    (0x8B, KeyCode::International5),
+    (0x8C, KeyCode::International6),
+    (0x8D, KeyCode::International7),
+    (0x8E, KeyCode::International8),
+    (0x8F, KeyCode::International9),
+    (0x90, KeyCode::LANG1),
+    (0x91, KeyCode::LANG2),
+    (0x92, KeyCode::LANG3),
+    (0x93, KeyCode::LANG4),
+    (0x94, KeyCode::LANG5),
+    (0x95, KeyCode::LANG6),
+    (0x96, KeyCode::LANG7),
+    (0x97, KeyCode::LANG8),
+    (0x98, KeyCode::LANG9),
+    (0x99, KeyCode::AlternateErase),
+    (0x9A, KeyCode::SysReq),
+    (0x9B, KeyCode::Cancel),
+    (0x9C, KeyCode::Clear),
+    (0x9D, KeyCode::Prior),
+    (0x9E, KeyCode::Return), //Return
+    (0x9F, KeyCode::Separator),
+    (0xA0, KeyCode::Out),
+    (0xA1, KeyCode::Oper),
+    (0xA2, KeyCode::ClearAgain),
+    (0xA3, KeyCode::CrSelProps),
+    (0xA4, KeyCode::ExSel),
+    (0xB0, KeyCode::Kp00),
+    (0xB1, KeyCode::Kp000),
+    (0xB2, KeyCode::ThousandsSeparator),
+    (0xB3, KeyCode::DecimalSeparator),
+    (0xB4, KeyCode::CurrencyUnit),
+    (0xB5, KeyCode::CurrencySubunit),
+    (0xB6, KeyCode::KpLeftBracket),
+    (0xB7, KeyCode::KpRightBracket),
+    (0xB8, KeyCode::KpLeftCurlyBracket),
+    (0xB9, KeyCode::KpRightCurlyBracket),
+    (0xBA, KeyCode::KpTab),
+    (0xBB, KeyCode::KpBackspace),
+    (0xBC, KeyCode::KpA),
+    (0xBD, KeyCode::KpB),
+    (0xBE, KeyCode::KpC),
+    (0xBF, KeyCode::KpD),
+    (0xC0, KeyCode::KpE),
+    (0xC1, KeyCode::KpF),
+    (0xC2, KeyCode::KpXOR),
+    (0xC3, KeyCode::KpCaret),
+    (0xC4, KeyCode::KpPercent),
+    (0xC5, KeyCode::KpLessThan),
+    (0xC6, KeyCode::KpGreaterThan),
+    (0xC7, KeyCode::Kpampersand),
+    (0xC8, KeyCode::KpDoubleampersand),
+    (0xC9, KeyCode::KpPipe),
+    (0xCA, KeyCode::KpDoublePipe),
+    (0xCB, KeyCode::KpColon),
+    (0xCC, KeyCode::KpHash),
+    (0xCD, KeyCode::KpSpace),
+    (0xCE, KeyCode::KpAt),
+    (0xCF, KeyCode::KpExclamation),
+    (0xD0, KeyCode::KpMemoryStore),
+    (0xD1, KeyCode::KpMemoryRecall),
+    (0xD2, KeyCode::KpMemoryClear),
+    (0xD3, KeyCode::KpMemoryAdd),
+    (0xD4, KeyCode::KpMemorySubtract),
+    (0xD5, KeyCode::KpMemoryMultiply),
+    (0xD6, KeyCode::KpMemoryDivide),
+    (0xD7, KeyCode::KpPlusMinus),
+    (0xD8, KeyCode::KpClear),
+    (0xD9, KeyCode::KpClearEntry),
+    (0xDA, KeyCode::KpBinary),
+    (0xDB, KeyCode::KpOctal),
+    (0xDC, KeyCode::KpDecimal),     //Keypad Decimal
+    (0xDD, KeyCode::KpHexadecimal), //Keypad Hexadecimal
+    (0xE0, KeyCode::LeftControl),   //LeftControl
+    (0xE1, KeyCode::LeftShift),
+    (0xE2, KeyCode::LeftAlt),
+    (0xE3, KeyCode::LeftGUI),
+    (0xE4, KeyCode::RightControl),
+    (0xE5, KeyCode::RightShift),
+    (0xE6, KeyCode::RightAlt),
+    (0xE7, KeyCode::RightGUI), //Right GUI
+];
+
+#[test]
+fn values_match_spec() {
+    for &(value, code) in SPEC {
+        assert_eq!(code as u8, value, "{:?}", code);
+        assert_eq!(KeyCode::try_from(value), Ok(code));
+    }
+    assert_eq!(KeyCode::T as u8, 0x17);
+    assert_eq!(KeyCode::U as u8, 0x18);
+}
+
+#[test]
+fn spec_is_complete() {
+    let defined = |value: u8| {
+        value <= 0xA4 || (0xB0..=0xDD).contains(&value) || (0xE0..=0xE7).contains(&value)
+    };
+    for value in 0..=0xE7u8 {
+        assert_eq!(
+            SPEC.iter().any(|&(v, _)| v == value),
+            defined(value),
+            "{:#04X}",
+            value
+        );
+    }
+}
+
+#[test]
+fn reserved_values() {
+    for value in (0xA5..=0xAF).chain(0xDE..=0xDF).chain(0xFC..=0xFF) {
+        assert_eq!(
+            KeyCode::try_from(value),
+            Err(UnknownKeyCode),
+            "{:#04X}",
+            value
+        );
+    }
+}
+
+#[test]
+fn all_in_order() {
+    assert!(KeyCode::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+    for &code in KeyCode::ALL {
+        assert_eq!(KeyCode::try_from(code as u8), Ok(code));
+    }
+}
+
+#[test]
+fn names() {
+    assert_eq!(KeyCode::KpEnter.name(), "KpEnter");
+    assert_eq!("kpdecimal".parse(), Ok(KeyCode::KpDecimal));
+    assert_eq!("LeftShift".parse(), Ok(KeyCode::LeftShift));
+    assert_eq!("Kb10".parse::<KeyCode>(), Err(UnknownKeyCode));
+    for &code in KeyCode::ALL {
+        assert_eq!(code.name().parse(), Ok(code));
+    }
+}
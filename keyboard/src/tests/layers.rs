@@ -0,0 +1,180 @@
+use super::{key as k, layered};
+use crate::keycode::KeyCode;
+use crate::{KeyAction, LayeredKeyboardLayout};
+
+const T: KeyAction = KeyAction::Transparent;
+
+//key 0 and 1 send keys, key 2 and 3 switch layers
+fn keymaps(layer_keys: [KeyAction; 2]) -> [[KeyAction; 4]; 3] {
+    [
+        [k(KeyCode::A), k(KeyCode::B), layer_keys[0], layer_keys[1]],
+        [k(KeyCode::Kp1), T, T, T],
+        [k(KeyCode::Kp2), T, T, T],
+    ]
+}
+
+#[test]
+fn base_layer() {
+    let mut layout = layered(keymaps([KeyAction::NoOp, KeyAction::NoOp]));
+
+    layout.press(0, 0);
+    layout.press(2, 0);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+    assert_eq!(layout.active_layer(), 0);
+
+    layout.release(0, 1);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn momentary_layer() {
+    let mut layout = layered(keymaps([KeyAction::MomentaryLayer(1), KeyAction::NoOp]));
+
+    layout.press(2, 0);
+    assert_eq!(layout.active_layer(), 1);
+    layout.press(0, 1);
+    assert_eq!(layout.held(), &[KeyCode::Kp1]);
+    layout.release(0, 2);
+
+    layout.release(2, 3);
+    assert_eq!(layout.active_layer(), 0);
+    layout.press(0, 4);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+}
+
+#[test]
+fn transparent_falls_through() {
+    let mut layout = layered(keymaps([KeyAction::MomentaryLayer(2), KeyAction::NoOp]));
+
+    layout.press(2, 0);
+    layout.press(1, 1);
+    assert_eq!(layout.held(), &[KeyCode::B]);
+}
+
+#[test]
+fn transparent_skips_inactive_layers() {
+    let mut layout = layered(keymaps([KeyAction::MomentaryLayer(2), KeyAction::NoOp]));
+
+    //layer 1 is off, so key 0 comes from layer 2 and key 1 falls through to layer 0
+    layout.press(2, 0);
+    assert!(!layout.is_active(1));
+    assert_eq!(layout.action(0), k(KeyCode::Kp2));
+    assert_eq!(layout.action(1), k(KeyCode::B));
+}
+
+#[test]
+fn highest_active_layer_wins() {
+    let mut layout = layered(keymaps([
+        KeyAction::MomentaryLayer(2),
+        KeyAction::MomentaryLayer(1),
+    ]));
+
+    layout.press(3, 0);
+    layout.press(2, 1);
+    assert_eq!(layout.action(0), k(KeyCode::Kp2));
+
+    layout.release(2, 2);
+    assert_eq!(layout.action(0), k(KeyCode::Kp1));
+}
+
+#[test]
+fn toggle_layer() {
+    let mut layout = layered(keymaps([KeyAction::ToggleLayer(1), KeyAction::NoOp]));
+
+    layout.press(2, 0);
+    layout.release(2, 1);
+    assert!(layout.is_active(1));
+    assert_eq!(layout.action(0), k(KeyCode::Kp1));
+
+    //layer 1 is transparent here, so the toggle key still reaches layer 0
+    layout.press(2, 2);
+    layout.release(2, 3);
+    assert!(!layout.is_active(1));
+    assert_eq!(layout.action(0), k(KeyCode::A));
+}
+
+#[test]
+fn to_layer_clears_other_layers() {
+    let mut layout = layered(keymaps([KeyAction::ToggleLayer(1), KeyAction::ToLayer(2)]));
+
+    layout.press(2, 0);
+    layout.release(2, 1);
+    layout.press(3, 2);
+    layout.release(3, 3);
+
+    assert!(!layout.is_active(1));
+    assert!(layout.is_active(2));
+    assert_eq!(layout.active_layer(), 2);
+}
+
+#[test]
+fn default_layer() {
+    let mut layout = layered(keymaps([KeyAction::DefaultLayer(1), KeyAction::ToLayer(0)]));
+
+    layout.press(2, 0);
+    layout.release(2, 1);
+    assert_eq!(layout.default_layer(), 1);
+    assert_eq!(layout.action(0), k(KeyCode::Kp1));
+
+    //to layer clears the layers above the default, not the default itself
+    layout.press(3, 2);
+    assert!(layout.is_active(1));
+    assert_eq!(layout.active_layer(), 1);
+    assert_eq!(layout.action(0), k(KeyCode::Kp1));
+}
+
+#[test]
+fn release_after_layer_change() {
+    let mut layout = layered(keymaps([KeyAction::MomentaryLayer(1), KeyAction::NoOp]));
+
+    //pressed on layer 1, released after layer 1 has gone
+    layout.press(2, 0);
+    layout.press(0, 1);
+    layout.release(2, 2);
+    assert_eq!(layout.held(), &[KeyCode::Kp1]);
+    layout.release(0, 3);
+    assert!(layout.held().is_empty());
+
+    //pressed on layer 0, layer 1 switched on while held
+    layout.press(0, 4);
+    layout.press(2, 5);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+    layout.release(0, 6);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn momentary_layer_released_after_covered() {
+    let mut layout = layered([
+        [KeyAction::MomentaryLayer(1), KeyAction::NoOp],
+        [T, KeyAction::MomentaryLayer(2)],
+        [KeyAction::NoOp, T],
+    ]);
+
+    //key 0 is NoOp on layer 2, releasing it must still switch layer 1 off
+    layout.press(0, 0);
+    layout.press(1, 1);
+    assert_eq!(layout.active_layer(), 2);
+    layout.release(0, 2);
+    assert!(!layout.is_active(1));
+    layout.release(1, 3);
+    assert_eq!(layout.active_layer(), 0);
+}
+
+#[test]
+fn modifiers_from_layers() {
+    let mut layout = layered([
+        [KeyAction::MomentaryLayer(1), k(KeyCode::A)],
+        [T, k(KeyCode::LeftShift)],
+    ]);
+
+    layout.press(0, 0);
+    layout.press(1, 1);
+    assert_eq!(layout.held(), &[KeyCode::LeftShift]);
+}
+
+#[test]
+#[should_panic]
+fn no_layers() {
+    LayeredKeyboardLayout::<1, 0>::new([]);
+}
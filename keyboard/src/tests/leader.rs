@@ -0,0 +1,114 @@
+use super::{key, keyboard, tapped, update, TestLayout};
+use crate::keycode::KeyCode;
+use crate::{KeyAction, KeyboardLayout, LayeredKeyboardLayout, Leader};
+
+const LEADERS: &[Leader] = &[
+    Leader::new(&[KeyCode::A], key(KeyCode::Escape)),
+    Leader::new(&[KeyCode::A, KeyCode::B], key(KeyCode::F1)),
+    Leader::new(&[KeyCode::B, KeyCode::B], key(KeyCode::F2)),
+];
+
+//key 0 is the leader key, keys 1 and 2 send A and B, key 3 switches to layer 1
+const KEYMAPS: [[KeyAction; 4]; 2] = [
+    [
+        KeyAction::Leader,
+        key(KeyCode::A),
+        key(KeyCode::B),
+        KeyAction::MomentaryLayer(1),
+    ],
+    [KeyAction::Transparent; 4],
+];
+
+#[test]
+fn sequence_fires_action() {
+    let mut layout = TestLayout::new(LayeredKeyboardLayout::new(KEYMAPS).leaders(LEADERS));
+
+    layout.tap(0, 0);
+    layout.tap(2, 100);
+    assert_eq!(layout.leader(), Some(&[KeyCode::B][..]));
+
+    layout.tap(2, 200);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::F2));
+    assert_eq!(layout.leader(), None);
+}
+
+#[test]
+fn prefix_waits_for_timeout() {
+    let mut layout = TestLayout::new(LayeredKeyboardLayout::new(KEYMAPS).leaders(LEADERS));
+
+    layout.tap(0, 0);
+    layout.tap(1, 100);
+    layout.tick(1099);
+    assert!(layout.take_outputs().is_empty());
+
+    layout.tick(1100);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::Escape));
+}
+
+#[test]
+fn longer_sequence_fires_without_waiting() {
+    let mut layout = TestLayout::new(LayeredKeyboardLayout::new(KEYMAPS).leaders(LEADERS));
+
+    layout.tap(0, 0);
+    layout.tap(1, 100);
+    layout.tap(2, 200);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::F1));
+}
+
+#[test]
+fn unknown_sequence_is_dropped() {
+    let mut layout = TestLayout::new(LayeredKeyboardLayout::new(KEYMAPS).leaders(LEADERS));
+
+    layout.tap(0, 0);
+    layout.tap(2, 100);
+    layout.tap(1, 200);
+    assert_eq!(layout.leader(), None);
+    assert!(layout.take_outputs().is_empty());
+
+    //keys type normally again
+    layout.tap(1, 300);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::A));
+}
+
+#[test]
+fn timeout_without_keys_cancels() {
+    let mut layout = TestLayout::new(LayeredKeyboardLayout::new(KEYMAPS).leaders(LEADERS));
+
+    layout.tap(0, 0);
+    assert_eq!(layout.leader(), Some(&[][..]));
+
+    layout.tick(1000);
+    assert_eq!(layout.leader(), None);
+    assert!(layout.take_outputs().is_empty());
+}
+
+#[test]
+fn other_action_cancels() {
+    let mut layout = TestLayout::new(LayeredKeyboardLayout::new(KEYMAPS).leaders(LEADERS));
+
+    layout.tap(0, 0);
+    layout.press(3, 100);
+    assert_eq!(layout.leader(), None);
+    assert!(layout.is_active(1));
+}
+
+#[test]
+fn state_shows_sequence() {
+    let mut keyboard = keyboard(
+        LayeredKeyboardLayout::new([[KeyAction::Leader, key(KeyCode::A), key(KeyCode::B)]])
+            .leaders(LEADERS)
+            .leader_timeout(300),
+    );
+    assert_eq!(keyboard.state().unwrap().leader, None);
+
+    update(&mut keyboard, 0, &[(0, true), (0, false)]);
+    update(&mut keyboard, 10, &[(1, true), (1, false)]);
+    assert_eq!(
+        keyboard.state().unwrap().leader.unwrap().as_slice(),
+        &[KeyCode::A]
+    );
+
+    update(&mut keyboard, 310, &[]);
+    assert_eq!(keyboard.state().unwrap().leader, None);
+    assert!(keyboard.has_report());
+}
@@ -0,0 +1,131 @@
+extern crate std;
+
+use super::{basic_keyboard, key, reports, update};
+use crate::keycode::{KeyCode, Modifiers};
+use crate::{KeyAction, MacroStep};
+use std::{vec, vec::Vec};
+
+const NONE: Modifiers = Modifiers::empty();
+
+//key 0 plays the macro, key 1 sends A
+fn keymap(steps: &'static [MacroStep]) -> [KeyAction; 3] {
+    [KeyAction::Macro(steps), key(KeyCode::A), KeyAction::NoOp]
+}
+
+#[test]
+fn shortcut_text_enter() {
+    static FORMAT: [MacroStep; 7] = [
+        MacroStep::Press(KeyCode::LeftControl),
+        MacroStep::Press(KeyCode::LeftShift),
+        MacroStep::Tap(KeyCode::P),
+        MacroStep::Release(KeyCode::LeftShift),
+        MacroStep::Release(KeyCode::LeftControl),
+        MacroStep::Text("fOr"),
+        MacroStep::Tap(KeyCode::Enter),
+    ];
+    let mut keyboard = basic_keyboard(keymap(&FORMAT));
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    let reports: Vec<_> = reports(&mut keyboard, 0)
+        .into_iter()
+        .map(|(_, modifiers, keycodes)| (modifiers, keycodes))
+        .collect();
+
+    let ctrl_shift = Modifiers::CTRL_LEFT | Modifiers::SHIFT_LEFT;
+    assert_eq!(
+        reports,
+        [
+            (ctrl_shift, vec![KeyCode::P]),
+            (NONE, vec![KeyCode::F]),
+            (Modifiers::SHIFT_LEFT, vec![KeyCode::O]),
+            (NONE, vec![KeyCode::R]),
+            (NONE, vec![KeyCode::Enter]),
+            (NONE, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn repeated_characters() {
+    static TEXT: [MacroStep; 1] = [MacroStep::Text("aA")];
+    let mut keyboard = basic_keyboard(keymap(&TEXT));
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    let reports: Vec<_> = reports(&mut keyboard, 0)
+        .into_iter()
+        .map(|(_, modifiers, keycodes)| (modifiers, keycodes))
+        .collect();
+
+    //the same key needs a release in between, so shift goes down on its own
+    assert_eq!(
+        reports,
+        [
+            (NONE, vec![KeyCode::A]),
+            (Modifiers::SHIFT_LEFT, vec![]),
+            (Modifiers::SHIFT_LEFT, vec![KeyCode::A]),
+            (NONE, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn delay() {
+    static DELAYED: [MacroStep; 3] = [
+        MacroStep::Tap(KeyCode::B),
+        MacroStep::Delay(100),
+        MacroStep::Tap(KeyCode::C),
+    ];
+    let mut keyboard = basic_keyboard(keymap(&DELAYED));
+
+    update(&mut keyboard, 10, &[(0, true)]);
+    let times: Vec<_> = reports(&mut keyboard, 10)
+        .into_iter()
+        .filter(|(_, _, keycodes)| !keycodes.is_empty())
+        .map(|(at, _, keycodes)| (at, keycodes))
+        .collect();
+
+    //the release of b is reported before the delay starts
+    assert_eq!(times, [(10, vec![KeyCode::B]), (112, vec![KeyCode::C])]);
+}
+
+#[test]
+fn unknown_characters_skipped() {
+    static TEXT: [MacroStep; 1] = [MacroStep::Text("a\u{b5}b")];
+    let mut keyboard = basic_keyboard(keymap(&TEXT));
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    let keys: Vec<_> = reports(&mut keyboard, 0)
+        .into_iter()
+        .flat_map(|(_, _, keycodes)| keycodes)
+        .collect();
+    assert_eq!(keys, [KeyCode::A, KeyCode::B]);
+}
+
+#[test]
+fn keys_wait_for_macro() {
+    static TEXT: [MacroStep; 1] = [MacroStep::Text("bc")];
+    let mut keyboard = basic_keyboard(keymap(&TEXT));
+
+    update(&mut keyboard, 0, &[(0, true), (1, true)]);
+    let reports: Vec<_> = reports(&mut keyboard, 0)
+        .into_iter()
+        .map(|(_, _, keycodes)| keycodes)
+        .collect();
+    assert_eq!(
+        reports,
+        [vec![KeyCode::B], vec![KeyCode::C], vec![KeyCode::A],]
+    );
+}
+
+#[test]
+fn long_text() {
+    static TEXT: [MacroStep; 1] = [MacroStep::Text(
+        "The quick brown fox jumps over the lazy dog, then the lazy dog sleeps on.",
+    )];
+    let mut keyboard = basic_keyboard(keymap(&TEXT));
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    let reports = reports(&mut keyboard, 0);
+    assert!(reports.len() > crate::MAX_OUTPUTS);
+    assert!(reports.last().unwrap().2.is_empty());
+}
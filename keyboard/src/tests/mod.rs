@@ -0,0 +1,225 @@
+extern crate std;
+
+use crate::keycode::{KeyCode, Modifiers};
+use crate::{
+    BasicKeyboardLayout, KeyAction, KeyEvent, KeyEvents, KeyState, Keyboard, KeyboardLayout,
+    KeyboardMatrix, LayeredKeyboardLayout, Output, Outputs,
+};
+use core::ops::{Deref, DerefMut};
+use std::vec::Vec;
+
+mod combos;
+mod consumer;
+mod keyboard;
+mod keycode;
+mod layers;
+mod leader;
+mod macros;
+mod mouse;
+mod one_shot;
+mod report;
+mod shortcuts;
+mod system;
+mod tap_dance;
+mod tap_hold;
+mod text;
+mod unicode;
+
+const fn key(code: KeyCode) -> KeyAction {
+    KeyAction::key(code)
+}
+
+fn tapped(code: KeyCode) -> [Output; 2] {
+    [Output::Press(code), Output::Release(code)]
+}
+
+/// Short items of a report descriptor as (tag and type, unsigned data)
+fn descriptor_items(descriptor: &[u8]) -> Vec<(u8, u32)> {
+    let mut items = Vec::new();
+    let mut rest = descriptor;
+    while let Some((&prefix, data)) = rest.split_first() {
+        let size = match prefix & 0b11 {
+            3 => 4,
+            n => n as usize,
+        };
+        let value = data[..size]
+            .iter()
+            .rev()
+            .fold(0, |value, &b| value << 8 | b as u32);
+        items.push((prefix & !0b11, value));
+        rest = &data[size..];
+    }
+    items
+}
+
+/// Drives a layout with key events, keeping the outputs and the keys the host would see held
+struct TestLayout<L> {
+    layout: L,
+    outputs: Vec<Output>,
+    held: Vec<KeyCode>,
+}
+
+impl<L> TestLayout<L> {
+    fn new(layout: L) -> TestLayout<L> {
+        TestLayout {
+            layout,
+            outputs: Vec::new(),
+            held: Vec::new(),
+        }
+    }
+
+    fn press<const N: usize>(&mut self, key: usize, at: u32)
+    where
+        L: KeyboardLayout<N>,
+    {
+        self.event(KeyEvent {
+            key,
+            pressed: true,
+            at,
+        });
+    }
+
+    fn release<const N: usize>(&mut self, key: usize, at: u32)
+    where
+        L: KeyboardLayout<N>,
+    {
+        self.event(KeyEvent {
+            key,
+            pressed: false,
+            at,
+        });
+    }
+
+    /// Press a key and release it 10ms later
+    fn tap<const N: usize>(&mut self, key: usize, at: u32)
+    where
+        L: KeyboardLayout<N>,
+    {
+        self.press(key, at);
+        self.release(key, at + 10);
+    }
+
+    fn event<const N: usize>(&mut self, event: KeyEvent)
+    where
+        L: KeyboardLayout<N>,
+    {
+        let mut outputs = Outputs::new();
+        self.layout.event(&event, &mut outputs);
+        self.apply(outputs);
+    }
+
+    fn tick<const N: usize>(&mut self, now: u32)
+    where
+        L: KeyboardLayout<N>,
+    {
+        let mut outputs = Outputs::new();
+        self.layout.tick(now, &mut outputs);
+        self.apply(outputs);
+    }
+
+    fn apply(&mut self, outputs: Outputs) {
+        for output in outputs {
+            match output {
+                Output::Press(code) | Output::PressWith(code, _) => self.held.push(code),
+                Output::Release(code) => {
+                    let i = self.held.iter().position(|&c| c == code).unwrap();
+                    self.held.remove(i);
+                }
+                _ => {}
+            }
+            self.outputs.push(output);
+        }
+    }
+
+    fn held(&self) -> &[KeyCode] {
+        &self.held
+    }
+
+    /// Outputs since the last call
+    fn take_outputs(&mut self) -> Vec<Output> {
+        core::mem::take(&mut self.outputs)
+    }
+}
+
+impl<L> Deref for TestLayout<L> {
+    type Target = L;
+
+    fn deref(&self) -> &L {
+        &self.layout
+    }
+}
+
+impl<L> DerefMut for TestLayout<L> {
+    fn deref_mut(&mut self) -> &mut L {
+        &mut self.layout
+    }
+}
+
+/// Test layout of a stack of keymaps
+fn layered<const N: usize, const LAYERS: usize>(
+    keymaps: [[KeyAction; N]; LAYERS],
+) -> TestLayout<LayeredKeyboardLayout<N, LAYERS>> {
+    TestLayout::new(LayeredKeyboardLayout::new(keymaps))
+}
+
+/// Matrix returning the events queued on it from the next update
+#[derive(Default)]
+struct TestMatrix {
+    events: KeyEvents<3>,
+}
+
+impl KeyboardMatrix<3> for TestMatrix {
+    type Error = ();
+
+    fn update(&mut self, _now: u32) -> Result<KeyEvents<3>, ()> {
+        Ok(core::mem::take(&mut self.events))
+    }
+
+    fn keys(&self) -> Result<[KeyState; 3], ()> {
+        Ok([KeyState::default(); 3])
+    }
+}
+
+type TestKeyboard<KL> = Keyboard<TestMatrix, KL, 3>;
+
+/// Keyboard scanning a test matrix through a layout
+fn keyboard<KL: KeyboardLayout<3>>(layout: KL) -> TestKeyboard<KL> {
+    Keyboard::new(TestMatrix::default(), layout)
+}
+
+/// Keyboard with a basic layout of three keys
+fn basic_keyboard(keymap: [KeyAction; 3]) -> TestKeyboard<BasicKeyboardLayout<3>> {
+    keyboard(BasicKeyboardLayout::new(keymap))
+}
+
+fn update<KL: KeyboardLayout<3>>(
+    keyboard: &mut TestKeyboard<KL>,
+    now: u32,
+    events: &[(usize, bool)],
+) {
+    for &(key, pressed) in events {
+        keyboard.matrix.events.push(KeyEvent {
+            key,
+            pressed,
+            at: now,
+        });
+    }
+    keyboard.update(now).unwrap();
+}
+
+/// Reports sent by polling every ms from `now` until nothing is left to send, with the time of each
+fn reports<KL: KeyboardLayout<3>>(
+    keyboard: &mut TestKeyboard<KL>,
+    mut now: u32,
+) -> Vec<(u32, Modifiers, Vec<KeyCode>)> {
+    let mut reports = Vec::new();
+    while keyboard.player.is_some() || !keyboard.outputs.is_empty() {
+        keyboard.update(now).unwrap();
+        if keyboard.has_report() {
+            let state = keyboard.next_state().unwrap();
+            reports.push((now, state.modifiers, state.keycodes.to_vec()));
+        }
+        now += 1;
+    }
+    reports
+}
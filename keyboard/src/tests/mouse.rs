@@ -0,0 +1,122 @@
+use super::{basic_keyboard, update, TestKeyboard};
+use crate::{
+    Acceleration, BasicKeyboardLayout, Curve, KeyAction, MouseAction, MouseReport, BUTTON_LEFT,
+};
+
+const LINEAR: Acceleration = Acceleration {
+    initial: 100,
+    max: 1100,
+    time_to_max: 1000,
+    curve: Curve::Linear,
+};
+
+#[test]
+fn linear_speed() {
+    assert_eq!(LINEAR.speed(0), 100);
+    assert_eq!(LINEAR.speed(500), 600);
+    assert_eq!(LINEAR.speed(1000), 1100);
+    assert_eq!(LINEAR.speed(60_000), 1100);
+}
+
+#[test]
+fn quadratic_speed() {
+    let quadratic = Acceleration {
+        curve: Curve::Quadratic,
+        ..LINEAR
+    };
+    assert_eq!(quadratic.speed(0), 100);
+    assert_eq!(quadratic.speed(500), 350);
+    assert_eq!(quadratic.speed(1000), 1100);
+
+    //slower than linear up to the max
+    assert!((1..1000).all(|held| quadratic.speed(held) <= LINEAR.speed(held)));
+}
+
+#[test]
+fn max_below_initial_keeps_initial() {
+    let constant = Acceleration { max: 0, ..LINEAR };
+    assert_eq!(constant.speed(0), 100);
+    assert_eq!(constant.speed(2000), 100);
+}
+
+//key 0 left button, key 1 moves right, key 2 scrolls up
+const KEYMAP: [KeyAction; 3] = [
+    KeyAction::Mouse(MouseAction::Button(BUTTON_LEFT)),
+    KeyAction::Mouse(MouseAction::Right),
+    KeyAction::Mouse(MouseAction::WheelUp),
+];
+
+//movement reported by polling every ms, as the firmware does on its scan tick
+fn poll(keyboard: &mut TestKeyboard<BasicKeyboardLayout<3>>, from: u32, to: u32) -> (i32, i32) {
+    let (mut x, mut wheel) = (0, 0);
+    for now in from..to {
+        update(keyboard, now, &[]);
+        while keyboard.has_report() {
+            keyboard.next_state().unwrap();
+        }
+        if let Some(report) = keyboard.mouse_report() {
+            x += report.x as i32;
+            wheel += report.wheel as i32;
+        }
+    }
+    (x, wheel)
+}
+
+#[test]
+fn click_spans_two_reports() {
+    let mut keyboard = basic_keyboard(KEYMAP);
+    keyboard.set_mouse_acceleration(LINEAR, LINEAR);
+
+    update(&mut keyboard, 0, &[(0, true), (0, false)]);
+    keyboard.next_state().unwrap();
+    assert_eq!(
+        keyboard.mouse_report(),
+        Some(MouseReport {
+            buttons: BUTTON_LEFT,
+            ..MouseReport::default()
+        })
+    );
+    assert_eq!(keyboard.mouse_report(), None);
+
+    keyboard.next_state().unwrap();
+    assert_eq!(keyboard.mouse_report(), Some(MouseReport::default()));
+}
+
+#[test]
+fn tap_moves_one_unit() {
+    let mut keyboard = basic_keyboard(KEYMAP);
+    keyboard.set_mouse_acceleration(LINEAR, LINEAR);
+
+    update(&mut keyboard, 0, &[(1, true), (1, false)]);
+    assert_eq!(poll(&mut keyboard, 0, 10), (1, 0));
+}
+
+#[test]
+fn held_key_accelerates() {
+    let mut keyboard = basic_keyboard(KEYMAP);
+    keyboard.set_mouse_acceleration(LINEAR, LINEAR);
+
+    update(&mut keyboard, 0, &[(1, true)]);
+    keyboard.next_state().unwrap();
+    //one unit for the press, then the area under the speed curve
+    let (first, _) = poll(&mut keyboard, 1, 101);
+    assert!((15..=17).contains(&first), "{first}");
+
+    poll(&mut keyboard, 101, 901);
+    let (later, _) = poll(&mut keyboard, 901, 1001);
+    assert!((104..=106).contains(&later), "{later}");
+
+    update(&mut keyboard, 1001, &[(1, false)]);
+    assert_eq!(poll(&mut keyboard, 1002, 1100), (0, 0));
+}
+
+#[test]
+fn wheel_scrolls() {
+    let mut keyboard = basic_keyboard(KEYMAP);
+    keyboard.set_mouse_acceleration(LINEAR, LINEAR);
+
+    update(&mut keyboard, 0, &[(2, true)]);
+    let (x, wheel) = poll(&mut keyboard, 0, 1000);
+    assert_eq!(x, 0);
+    assert!((600..=602).contains(&wheel), "{wheel}");
+}
@@ -0,0 +1,186 @@
+use super::{key, layered};
+use crate::keycode::{KeyCode, Modifiers};
+use crate::{KeyAction, KeyboardLayout, OneShots, Output};
+
+const CTRL: KeyAction = KeyAction::OneShotMod(Modifiers::CTRL_LEFT);
+const SHIFT: KeyAction = KeyAction::OneShotMod(Modifiers::SHIFT_LEFT);
+
+//keys 0 and 1 are one-shot ctrl and shift, key 2 one-shot layer 1, key 3 sends A, or B on layer 1
+const KEYMAPS: [[KeyAction; 4]; 2] = [
+    [CTRL, SHIFT, KeyAction::OneShotLayer(1), key(KeyCode::A)],
+    [
+        KeyAction::Transparent,
+        KeyAction::Transparent,
+        KeyAction::Transparent,
+        key(KeyCode::B),
+    ],
+];
+
+#[test]
+fn modifier_applies_to_next_key() {
+    let mut layout = layered(KEYMAPS);
+
+    layout.tap(0, 0);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl]);
+    assert_eq!(layout.one_shots().modifiers, Modifiers::CTRL_LEFT);
+
+    layout.press(3, 100);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl, KeyCode::A]);
+    assert!(layout.one_shots().is_empty());
+
+    layout.release(3, 150);
+    assert!(layout.held().is_empty());
+
+    layout.tap(3, 200);
+    assert_eq!(
+        layout.take_outputs()[4..],
+        [Output::Press(KeyCode::A), Output::Release(KeyCode::A)]
+    );
+}
+
+#[test]
+fn modifier_leaves_rolled_key() {
+    let mut layout = layered([[SHIFT, key(KeyCode::T), key(KeyCode::H)]]);
+
+    layout.tap(0, 0);
+    layout.press(1, 100);
+    assert_eq!(layout.held(), &[KeyCode::LeftShift, KeyCode::T]);
+
+    layout.press(2, 120);
+    assert_eq!(layout.held(), &[KeyCode::T, KeyCode::H]);
+
+    layout.release(1, 150);
+    layout.release(2, 170);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn modifiers_stack() {
+    let mut layout = layered(KEYMAPS);
+
+    layout.tap(0, 0);
+    layout.tap(1, 50);
+    layout.press(3, 100);
+    assert_eq!(
+        layout.held(),
+        &[KeyCode::LeftControl, KeyCode::LeftShift, KeyCode::A]
+    );
+
+    layout.release(3, 150);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn double_tap_locks_modifier() {
+    let mut layout = layered(KEYMAPS);
+
+    layout.tap(1, 0);
+    layout.tap(1, 50);
+    assert_eq!(
+        layout.one_shots(),
+        OneShots {
+            locked_modifiers: Modifiers::SHIFT_LEFT,
+            ..OneShots::default()
+        }
+    );
+
+    layout.tap(3, 100);
+    layout.tap(3, 200);
+    assert_eq!(layout.held(), &[KeyCode::LeftShift]);
+
+    //a third tap unlocks
+    layout.tap(1, 300);
+    assert!(layout.held().is_empty());
+    assert!(layout.one_shots().is_empty());
+}
+
+#[test]
+fn layer_applies_to_next_key() {
+    let mut layout = layered(KEYMAPS);
+
+    layout.tap(2, 0);
+    assert!(layout.is_active(1));
+
+    layout.tap(3, 100);
+    assert!(!layout.is_active(1));
+    layout.tap(3, 200);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::B),
+            Output::Release(KeyCode::B),
+            Output::Press(KeyCode::A),
+            Output::Release(KeyCode::A)
+        ]
+    );
+}
+
+#[test]
+fn double_tap_locks_layer() {
+    let mut layout = layered(KEYMAPS);
+
+    layout.tap(2, 0);
+    layout.tap(2, 50);
+    assert_eq!(layout.one_shots().locked_layers, 0b10);
+
+    layout.tap(3, 100);
+    assert!(layout.is_active(1));
+
+    layout.tap(2, 200);
+    assert!(!layout.is_active(1));
+    assert!(layout.one_shots().is_empty());
+}
+
+#[test]
+fn layer_and_modifier_together() {
+    let mut layout = layered(KEYMAPS);
+
+    layout.tap(2, 0);
+    layout.tap(0, 50);
+    layout.press(3, 100);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl, KeyCode::B]);
+    assert!(!layout.is_active(1));
+}
+
+//key 0 is one-shot layer 1, key 1 toggles layer 1, key 2 goes to layer 0, key 3 sends A or B
+const LAYER_SWITCHES: [[KeyAction; 4]; 2] = [
+    [
+        KeyAction::OneShotLayer(1),
+        KeyAction::ToggleLayer(1),
+        KeyAction::ToLayer(0),
+        key(KeyCode::A),
+    ],
+    [
+        KeyAction::Transparent,
+        KeyAction::Transparent,
+        KeyAction::Transparent,
+        key(KeyCode::B),
+    ],
+];
+
+#[test]
+fn toggled_layer_stays_after_one_shot() {
+    let mut layout = layered(LAYER_SWITCHES);
+
+    layout.tap(1, 0);
+    layout.tap(0, 50);
+    layout.tap(3, 100);
+    assert!(layout.is_active(1));
+    assert!(layout.one_shots().is_empty());
+
+    layout.tap(1, 200);
+    assert!(!layout.is_active(1));
+}
+
+#[test]
+fn to_layer_unlocks_layer() {
+    let mut layout = layered(LAYER_SWITCHES);
+
+    layout.tap(0, 0);
+    layout.tap(0, 50);
+    assert!(layout.is_active(1));
+
+    layout.tap(2, 100);
+    assert!(!layout.is_active(1));
+    assert!(layout.one_shots().is_empty());
+}
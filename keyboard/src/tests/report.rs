@@ -0,0 +1,125 @@
+use super::{basic_keyboard, descriptor_items, key};
+use crate::keycode::{KeyCode, Modifiers};
+use crate::report::{NKRO_DESCRIPTOR, NKRO_MAX_USAGE, NKRO_REPORT_LEN};
+use crate::{KeyAction, KeyState, KeyboardState, OneShots};
+use arrayvec::ArrayVec;
+
+fn state(modifiers: Modifiers, keycodes: &[KeyCode]) -> KeyboardState<12> {
+    KeyboardState {
+        modifiers,
+        keycodes: keycodes.iter().copied().collect(),
+        keys: [KeyState::default(); 12],
+        leader: None,
+        one_shots: OneShots::default(),
+        consumer: 0,
+        system: 0,
+    }
+}
+
+#[test]
+fn boot_report_keys() {
+    let state = state(Modifiers::SHIFT_LEFT, &[KeyCode::A, KeyCode::Kp1]);
+    assert_eq!(state.boot_report(), [0x02, 0, 0x04, 0x59, 0, 0, 0, 0]);
+}
+
+#[test]
+fn boot_report_rollover() {
+    let keys = [
+        KeyCode::A,
+        KeyCode::B,
+        KeyCode::C,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::F,
+        KeyCode::G,
+    ];
+    assert_eq!(
+        state(Modifiers::empty(), &keys[..6]).boot_report()[2..],
+        [4, 5, 6, 7, 8, 9]
+    );
+    assert_eq!(
+        state(Modifiers::CTRL_LEFT, &keys).boot_report(),
+        [1, 0, 1, 1, 1, 1, 1, 1]
+    );
+}
+
+#[test]
+fn nkro_report_has_every_key() {
+    let keys: ArrayVec<KeyCode, 12> = [
+        KeyCode::A,
+        KeyCode::B,
+        KeyCode::C,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::F,
+        KeyCode::G,
+        KeyCode::Kp0,
+    ]
+    .into_iter()
+    .collect();
+    let report = state(Modifiers::GUI_RIGHT, &keys).nkro_report();
+
+    assert_eq!(report[0], 0x80);
+    for usage in 0..=NKRO_MAX_USAGE {
+        let set = report[1 + usage as usize / 8] & 1 << (usage % 8) != 0;
+        assert_eq!(
+            set,
+            keys.iter().any(|&k| k as u8 == usage),
+            "usage {usage:#x}"
+        );
+    }
+}
+
+#[test]
+fn nkro_report_leaves_out_unofficial_media_keys() {
+    let report = state(Modifiers::empty(), &[KeyCode::MediaPlayPause]).nkro_report();
+    assert_eq!(report, [0; NKRO_REPORT_LEN]);
+}
+
+#[test]
+fn nkro_descriptor_matches_report() {
+    let items = descriptor_items(&NKRO_DESCRIPTOR);
+
+    //input bits add up to the report, tracking the global report size and count
+    let (mut size, mut count, mut input_bits) = (0, 0, 0);
+    for &(tag, value) in &items {
+        match tag {
+            0x74 => size = value,
+            0x94 => count = value,
+            0x80 => input_bits += size * count,
+            _ => {}
+        }
+    }
+    assert_eq!(input_bits as usize, NKRO_REPORT_LEN * 8);
+
+    assert_eq!(&items[..3], [(0x04, 0x01), (0x08, 0x06), (0xA0, 0x01)]);
+    assert_eq!(items.last(), Some(&(0xC0, 0)));
+    assert!(items.contains(&(0x28, NKRO_MAX_USAGE as u32)));
+}
+
+#[test]
+fn more_codes_than_keys() {
+    let mut keyboard = basic_keyboard([KeyAction::NoOp; 3]);
+    //a macro or layers can hold more codes than there are keys
+    let keys = [
+        KeyCode::A,
+        KeyCode::B,
+        KeyCode::C,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::F,
+        KeyCode::G,
+    ];
+    for code in keys {
+        keyboard.trigger(key(code), true);
+    }
+
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.keycodes.as_slice(), keys);
+    assert_eq!(state.boot_report(), [0, 0, 1, 1, 1, 1, 1, 1]);
+    let set: u32 = state.nkro_report()[1..]
+        .iter()
+        .map(|byte| byte.count_ones())
+        .sum();
+    assert_eq!(set, 7);
+}
@@ -0,0 +1,125 @@
+extern crate std;
+
+use super::{basic_keyboard, key, reports, update};
+use crate::keycode::{KeyCode, Modifiers};
+use crate::KeyAction;
+use std::vec;
+
+//key 0 is ctrl+c, key 1 is '!', key 2 sends A
+fn keymap(third: KeyAction) -> [KeyAction; 3] {
+    [
+        KeyAction::shortcut(Modifiers::CTRL_LEFT, KeyCode::C),
+        KeyAction::shortcut(Modifiers::SHIFT_LEFT, KeyCode::Kb1),
+        third,
+    ]
+}
+
+#[test]
+fn modifiers_held_with_key() {
+    let mut keyboard = basic_keyboard(keymap(key(KeyCode::A)));
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    update(&mut keyboard, 1, &[(0, false)]);
+    assert_eq!(
+        reports(&mut keyboard, 1),
+        [
+            (1, Modifiers::CTRL_LEFT, vec![KeyCode::C]),
+            (2, Modifiers::empty(), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn later_key_masks_modifiers() {
+    let mut keyboard = basic_keyboard(keymap(key(KeyCode::A)));
+
+    update(&mut keyboard, 0, &[(1, true)]);
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.modifiers, Modifiers::SHIFT_LEFT);
+
+    //'!' still held, A comes out lowercase
+    update(&mut keyboard, 10, &[(2, true)]);
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.modifiers, Modifiers::empty());
+    assert_eq!(state.keycodes.as_slice(), &[KeyCode::Kb1, KeyCode::A]);
+
+    //the masked modifiers don't come back
+    update(&mut keyboard, 20, &[(2, false)]);
+    let state = keyboard.next_state().unwrap();
+    assert_eq!(state.modifiers, Modifiers::empty());
+    assert_eq!(state.keycodes.as_slice(), &[KeyCode::Kb1]);
+}
+
+#[test]
+fn overlapping_shortcuts() {
+    let mut keyboard = basic_keyboard(keymap(key(KeyCode::A)));
+
+    update(&mut keyboard, 0, &[(0, true)]);
+    update(&mut keyboard, 10, &[(1, true)]);
+    update(&mut keyboard, 20, &[(0, false)]);
+    update(&mut keyboard, 30, &[(1, false)]);
+    assert_eq!(
+        reports(&mut keyboard, 30),
+        [
+            (30, Modifiers::CTRL_LEFT, vec![KeyCode::C]),
+            //ctrl is masked as '!' is pressed, with C released in the same report
+            (31, Modifiers::SHIFT_LEFT, vec![KeyCode::Kb1]),
+            (32, Modifiers::empty(), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn shortcut_pressed_with_plain_key_gets_own_report() {
+    let mut keyboard = basic_keyboard(keymap(key(KeyCode::A)));
+
+    //pressed in the same scan, ctrl must not reach A
+    update(&mut keyboard, 0, &[(0, true), (2, true)]);
+    assert_eq!(
+        reports(&mut keyboard, 1),
+        [
+            (1, Modifiers::CTRL_LEFT, vec![KeyCode::C]),
+            (2, Modifiers::empty(), vec![KeyCode::C, KeyCode::A]),
+        ]
+    );
+}
+
+#[test]
+fn plain_key_pressed_with_shortcut_gets_own_report() {
+    let mut keyboard = basic_keyboard(keymap(key(KeyCode::A)));
+
+    //the plain key first, ctrl must not reach it either
+    update(&mut keyboard, 0, &[(2, true), (0, true)]);
+    assert_eq!(
+        reports(&mut keyboard, 1),
+        [
+            (1, Modifiers::empty(), vec![KeyCode::A]),
+            (2, Modifiers::CTRL_LEFT, vec![KeyCode::A, KeyCode::C]),
+        ]
+    );
+}
+
+#[test]
+fn held_modifier_key_adds_to_shortcut() {
+    let mut keyboard = basic_keyboard(keymap(key(KeyCode::LeftShift)));
+
+    update(&mut keyboard, 0, &[(2, true)]);
+    assert_eq!(
+        reports(&mut keyboard, 0),
+        [(0, Modifiers::SHIFT_LEFT, vec![])]
+    );
+
+    update(&mut keyboard, 10, &[(0, true)]);
+    update(&mut keyboard, 20, &[(0, false)]);
+    assert_eq!(
+        reports(&mut keyboard, 20),
+        [
+            (
+                20,
+                Modifiers::SHIFT_LEFT | Modifiers::CTRL_LEFT,
+                vec![KeyCode::C]
+            ),
+            (21, Modifiers::SHIFT_LEFT, vec![]),
+        ]
+    );
+}
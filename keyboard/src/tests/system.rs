@@ -0,0 +1,42 @@
+use super::{basic_keyboard, descriptor_items, update};
+use crate::system::{self, SYSTEM_CONTROL_DESCRIPTOR};
+use crate::KeyAction;
+
+#[test]
+fn descriptor() {
+    assert_eq!(
+        descriptor_items(&SYSTEM_CONTROL_DESCRIPTOR),
+        [
+            (0x04, 0x01), //usage page generic desktop
+            (0x08, 0x80), //usage system control
+            (0xA0, 0x01), //collection application
+            (0x18, 0x81), //usage minimum power down
+            (0x28, 0x83), //usage maximum wake up
+            (0x14, 0x81), //logical minimum
+            (0x24, 0x83), //logical maximum
+            (0x74, 8),    //report size
+            (0x94, 1),    //report count
+            (0x80, 0x00), //input data, array, absolute
+            (0xC0, 0),    //end collection
+        ]
+    );
+}
+
+#[test]
+fn system_key_held() {
+    let mut keyboard = basic_keyboard([
+        KeyAction::System(system::SLEEP),
+        KeyAction::System(system::WAKE_UP),
+        KeyAction::NoOp,
+    ]);
+
+    update(&mut keyboard, 0, &[(0, true), (1, true)]);
+    assert_eq!(keyboard.next_state().unwrap().system, system::SLEEP);
+    assert_eq!(keyboard.next_state().unwrap().system, system::WAKE_UP);
+
+    //sleep isn't sent a second time once wake up is released
+    update(&mut keyboard, 10, &[(1, false), (0, false)]);
+    assert_eq!(keyboard.next_state().unwrap().system, 0);
+    assert_eq!(keyboard.next_state().unwrap().system, 0);
+    assert!(!keyboard.has_report());
+}
@@ -0,0 +1,156 @@
+use super::{key, layered, tapped};
+use crate::keycode::KeyCode;
+use crate::{KeyAction, Output, TapDance};
+
+const DOT: TapDance =
+    TapDance::new(&[key(KeyCode::KpDot), key(KeyCode::Comma)]).hold(KeyAction::MomentaryLayer(1));
+const TRIPLE: TapDance = TapDance::new(&[key(KeyCode::A), key(KeyCode::B), key(KeyCode::C)])
+    .tap_hold(key(KeyCode::LeftShift));
+
+//key 0 is the tap dance key, key 1 sends Kp1, or Kp2 on layer 1
+fn keymaps(tap_dance: &'static TapDance) -> [[KeyAction; 2]; 2] {
+    [
+        [KeyAction::TapDance(tap_dance), key(KeyCode::Kp1)],
+        [KeyAction::Transparent, key(KeyCode::Kp2)],
+    ]
+}
+
+#[test]
+fn single_tap() {
+    let mut layout = layered(keymaps(&DOT));
+
+    layout.press(0, 0);
+    layout.release(0, 50);
+    layout.tick(249);
+    assert!(layout.take_outputs().is_empty());
+
+    layout.tick(250);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::KpDot));
+}
+
+#[test]
+fn double_tap_finishes_on_release() {
+    let mut layout = layered(keymaps(&DOT));
+
+    layout.press(0, 0);
+    layout.release(0, 50);
+    layout.press(0, 150);
+    assert!(layout.take_outputs().is_empty());
+
+    //no more taps can change the action
+    layout.release(0, 200);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::Comma));
+}
+
+#[test]
+fn hold_switches_layer() {
+    let mut layout = layered(keymaps(&DOT));
+
+    layout.press(0, 0);
+    layout.tick(200);
+    assert!(layout.is_active(1));
+
+    layout.press(1, 250);
+    layout.release(1, 300);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::Kp2));
+
+    layout.release(0, 350);
+    assert!(!layout.is_active(1));
+    assert!(layout.take_outputs().is_empty());
+}
+
+#[test]
+fn other_key_interrupts() {
+    let mut layout = layered(keymaps(&DOT));
+
+    layout.press(0, 0);
+    layout.release(0, 50);
+    layout.press(1, 100);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::KpDot),
+            Output::Release(KeyCode::KpDot),
+            Output::Press(KeyCode::Kp1)
+        ]
+    );
+}
+
+#[test]
+fn interrupted_while_held_taps() {
+    let mut layout = layered(keymaps(&DOT));
+
+    //a quick roll onto the next key is not a hold
+    layout.press(0, 0);
+    layout.press(1, 50);
+    assert!(!layout.is_active(1));
+    assert_eq!(layout.held(), &[KeyCode::KpDot, KeyCode::Kp1]);
+
+    layout.release(0, 100);
+    layout.release(1, 150);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn triple_tap() {
+    let mut layout = layered(keymaps(&TRIPLE));
+
+    for at in [0, 100, 200] {
+        layout.press(0, at);
+        layout.release(0, at + 50);
+    }
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::C));
+}
+
+#[test]
+fn more_taps_start_a_new_dance() {
+    let mut layout = layered(keymaps(&TRIPLE));
+
+    for at in [0, 100, 200, 300] {
+        layout.press(0, at);
+        layout.release(0, at + 50);
+    }
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::C));
+
+    layout.tick(550);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::A));
+}
+
+#[test]
+fn tap_then_hold() {
+    let mut layout = layered(keymaps(&TRIPLE));
+
+    layout.press(0, 0);
+    layout.release(0, 50);
+    layout.press(0, 100);
+    layout.tick(300);
+    assert_eq!(layout.held(), &[KeyCode::LeftShift]);
+
+    layout.release(0, 400);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn single_hold_without_hold_action_holds_tap() {
+    let mut layout = layered(keymaps(&TRIPLE));
+
+    layout.press(0, 0);
+    layout.tick(200);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+
+    layout.release(0, 500);
+    assert!(layout.held().is_empty());
+}
+
+#[test]
+fn wrapping_timestamps() {
+    let mut layout = layered(keymaps(&DOT));
+
+    layout.press(0, u32::MAX - 50);
+    layout.release(0, u32::MAX);
+    layout.tick(100);
+    assert!(layout.take_outputs().is_empty());
+
+    layout.tick(200);
+    assert_eq!(layout.take_outputs(), tapped(KeyCode::KpDot));
+}
@@ -0,0 +1,234 @@
+use super::{key, layered};
+use crate::keycode::KeyCode;
+use crate::{KeyAction, Output, TapHold};
+
+const ENTER_CTRL: TapHold = TapHold::new(key(KeyCode::KpEnter), key(KeyCode::LeftControl));
+
+//key 0 is the tap-hold key, key 1 sends A, or Kp1 on layer 1
+fn keymaps(tap_hold: &'static TapHold) -> [[KeyAction; 2]; 2] {
+    [
+        [KeyAction::TapHold(tap_hold), key(KeyCode::A)],
+        [KeyAction::Transparent, key(KeyCode::Kp1)],
+    ]
+}
+
+#[test]
+fn tap() {
+    let mut layout = layered(keymaps(&ENTER_CTRL));
+
+    layout.press(0, 0);
+    layout.tick(199);
+    assert!(layout.take_outputs().is_empty());
+
+    layout.release(0, 199);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::KpEnter),
+            Output::Release(KeyCode::KpEnter)
+        ]
+    );
+}
+
+#[test]
+fn hold() {
+    let mut layout = layered(keymaps(&ENTER_CTRL));
+
+    layout.press(0, 0);
+    layout.tick(200);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl]);
+
+    layout.press(1, 250);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl, KeyCode::A]);
+
+    layout.release(1, 300);
+    layout.release(0, 350);
+    assert!(layout.held().is_empty());
+    assert!(!layout
+        .take_outputs()
+        .contains(&Output::Press(KeyCode::KpEnter)));
+}
+
+#[test]
+fn hold_decided_by_later_event() {
+    let mut layout = layered(keymaps(&ENTER_CTRL));
+
+    //no tick in between, the next event's timestamp is past the term
+    layout.press(0, 0);
+    layout.press(1, 300);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::LeftControl),
+            Output::Press(KeyCode::A)
+        ]
+    );
+}
+
+#[test]
+fn tapping_term() {
+    static SHORT: TapHold = ENTER_CTRL.tapping_term(50);
+    let mut layout = layered(keymaps(&SHORT));
+
+    layout.press(0, 0);
+    layout.tick(49);
+    assert!(layout.held().is_empty());
+    layout.tick(50);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl]);
+}
+
+#[test]
+fn clock_wraps() {
+    let mut layout = layered(keymaps(&ENTER_CTRL));
+
+    layout.press(0, u32::MAX - 100);
+    layout.tick(50);
+    assert!(layout.held().is_empty());
+    layout.tick(99);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl]);
+}
+
+#[test]
+fn other_key_tapped_within_term_is_a_tap() {
+    let mut layout = layered(keymaps(&ENTER_CTRL));
+
+    //rolling from the tap-hold key onto another key
+    layout.press(0, 0);
+    layout.press(1, 50);
+    assert!(layout.take_outputs().is_empty());
+    layout.release(0, 100);
+    layout.release(1, 150);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::KpEnter),
+            Output::Press(KeyCode::A),
+            Output::Release(KeyCode::KpEnter),
+            Output::Release(KeyCode::A)
+        ]
+    );
+
+    //nested tap without permissive hold
+    layout.press(0, 1000);
+    layout.press(1, 1050);
+    layout.release(1, 1100);
+    layout.release(0, 1150);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::KpEnter),
+            Output::Press(KeyCode::A),
+            Output::Release(KeyCode::A),
+            Output::Release(KeyCode::KpEnter)
+        ]
+    );
+}
+
+#[test]
+fn hold_on_other_key_press() {
+    static EAGER: TapHold = ENTER_CTRL.hold_on_other_key_press();
+    let mut layout = layered(keymaps(&EAGER));
+
+    layout.press(0, 0);
+    layout.press(1, 10);
+    assert_eq!(layout.held(), &[KeyCode::LeftControl, KeyCode::A]);
+
+    layout.release(0, 20);
+    layout.release(1, 30);
+    assert!(layout.held().is_empty());
+    assert!(!layout
+        .take_outputs()
+        .contains(&Output::Press(KeyCode::KpEnter)));
+}
+
+#[test]
+fn permissive_hold() {
+    static PERMISSIVE: TapHold = ENTER_CTRL.permissive_hold();
+    let mut layout = layered(keymaps(&PERMISSIVE));
+
+    //another key tapped inside the tap-hold key
+    layout.press(0, 0);
+    layout.press(1, 10);
+    assert!(layout.take_outputs().is_empty());
+    layout.release(1, 20);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::LeftControl),
+            Output::Press(KeyCode::A),
+            Output::Release(KeyCode::A)
+        ]
+    );
+    layout.release(0, 30);
+    assert_eq!(
+        layout.take_outputs(),
+        [Output::Release(KeyCode::LeftControl)]
+    );
+
+    //rolling over is still a tap
+    layout.press(0, 1000);
+    layout.press(1, 1010);
+    layout.release(0, 1020);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::KpEnter),
+            Output::Press(KeyCode::A),
+            Output::Release(KeyCode::KpEnter)
+        ]
+    );
+}
+
+#[test]
+fn retro_tapping() {
+    static RETRO: TapHold = ENTER_CTRL.retro_tapping();
+    let mut layout = layered(keymaps(&RETRO));
+
+    layout.press(0, 0);
+    layout.tick(500);
+    layout.release(0, 600);
+    assert_eq!(
+        layout.take_outputs(),
+        [
+            Output::Press(KeyCode::LeftControl),
+            Output::Release(KeyCode::LeftControl),
+            Output::Press(KeyCode::KpEnter),
+            Output::Release(KeyCode::KpEnter)
+        ]
+    );
+
+    //used as a hold, no tap on release
+    layout.press(0, 1000);
+    layout.tick(1500);
+    layout.press(1, 1600);
+    layout.release(1, 1700);
+    layout.release(0, 1800);
+    assert!(!layout
+        .take_outputs()
+        .contains(&Output::Press(KeyCode::KpEnter)));
+}
+
+#[test]
+fn layer_tap() {
+    static ENTER_LAYER: TapHold = TapHold::new(key(KeyCode::KpEnter), KeyAction::MomentaryLayer(1));
+    let mut layout = layered(keymaps(&ENTER_LAYER));
+
+    //key pressed before the term is decided on the hold layer
+    layout.press(0, 0);
+    layout.press(1, 50);
+    layout.tick(200);
+    assert_eq!(layout.active_layer(), 1);
+    assert_eq!(layout.held(), &[KeyCode::Kp1]);
+
+    //released after the layer has gone
+    layout.release(0, 300);
+    assert_eq!(layout.active_layer(), 0);
+    layout.release(1, 350);
+    assert!(layout.held().is_empty());
+
+    layout.press(0, 1000);
+    layout.release(0, 1010);
+    layout.press(1, 1020);
+    assert_eq!(layout.held(), &[KeyCode::A]);
+}
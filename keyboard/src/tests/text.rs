@@ -0,0 +1,192 @@
+extern crate std;
+
+use super::{basic_keyboard, reports, update};
+use crate::keycode::{KeyCode, Modifiers};
+use crate::{HostLayout, KeyAction, Stroke, TextError};
+use std::{vec, vec::Vec};
+
+const NONE: Modifiers = Modifiers::empty();
+const SHIFT: Modifiers = Modifiers::SHIFT_LEFT;
+const ALT_GR: Modifiers = Modifiers::ALT_RIGHT;
+
+//(character, modifiers, key, dead key) from each layout's reference chart
+const US: &[(char, Modifiers, KeyCode, bool)] = &[
+    ('a', NONE, KeyCode::A, false),
+    ('Z', SHIFT, KeyCode::Z, false),
+    ('1', NONE, KeyCode::Kb1, false),
+    ('0', NONE, KeyCode::Kb0, false),
+    ('@', SHIFT, KeyCode::Kb2, false),
+    ('#', SHIFT, KeyCode::Kb3, false),
+    ('"', SHIFT, KeyCode::Apostrophy, false),
+    ('\\', NONE, KeyCode::BackslashANSI, false),
+    ('~', SHIFT, KeyCode::Grave, false),
+];
+
+const UK: &[(char, Modifiers, KeyCode, bool)] = &[
+    ('y', NONE, KeyCode::Y, false),
+    ('@', SHIFT, KeyCode::Apostrophy, false),
+    ('#', NONE, KeyCode::Hash, false),
+    ('"', SHIFT, KeyCode::Kb2, false),
+    ('£', SHIFT, KeyCode::Kb3, false),
+    ('€', ALT_GR, KeyCode::Kb4, false),
+    ('\\', NONE, KeyCode::BackslashISO, false),
+    ('~', SHIFT, KeyCode::Hash, false),
+    ('?', SHIFT, KeyCode::ForwardSlash, false),
+];
+
+const DE: &[(char, Modifiers, KeyCode, bool)] = &[
+    ('y', NONE, KeyCode::Z, false),
+    ('Z', SHIFT, KeyCode::Y, false),
+    ('@', ALT_GR, KeyCode::Q, false),
+    ('#', NONE, KeyCode::Hash, false),
+    ('"', SHIFT, KeyCode::Kb2, false),
+    ('ß', NONE, KeyCode::Minus, false),
+    ('ü', NONE, KeyCode::LeftBracket, false),
+    ('Ä', SHIFT, KeyCode::Apostrophy, false),
+    ('{', ALT_GR, KeyCode::Kb7, false),
+    ('-', NONE, KeyCode::ForwardSlash, false),
+    ('^', NONE, KeyCode::Grave, true),
+    ('`', SHIFT, KeyCode::Equals, true),
+];
+
+const FR: &[(char, Modifiers, KeyCode, bool)] = &[
+    ('a', NONE, KeyCode::Q, false),
+    ('Q', SHIFT, KeyCode::A, false),
+    ('w', NONE, KeyCode::Z, false),
+    ('z', NONE, KeyCode::W, false),
+    ('m', NONE, KeyCode::Semicolon, false),
+    ('1', SHIFT, KeyCode::Kb1, false),
+    ('0', SHIFT, KeyCode::Kb0, false),
+    ('&', NONE, KeyCode::Kb1, false),
+    ('é', NONE, KeyCode::Kb2, false),
+    ('@', ALT_GR, KeyCode::Kb0, false),
+    ('#', ALT_GR, KeyCode::Kb3, false),
+    ('"', NONE, KeyCode::Kb3, false),
+    (',', NONE, KeyCode::M, false),
+    ('.', SHIFT, KeyCode::Comma, false),
+    ('!', NONE, KeyCode::ForwardSlash, false),
+    ('~', ALT_GR, KeyCode::Kb2, true),
+];
+
+fn printable_ascii() -> impl Iterator<Item = char> {
+    (' '..='~').chain(['\n', '\t'])
+}
+
+#[test]
+fn reference_tables() {
+    for (layout, table) in [
+        (HostLayout::Us, US),
+        (HostLayout::Uk, UK),
+        (HostLayout::De, DE),
+        (HostLayout::Fr, FR),
+    ] {
+        for &(c, modifiers, code, dead) in table {
+            assert_eq!(
+                layout.stroke(c),
+                Ok(Stroke {
+                    modifiers,
+                    code,
+                    dead
+                }),
+                "{:?} {:?}",
+                layout,
+                c
+            );
+        }
+    }
+}
+
+#[test]
+fn every_ascii_character() {
+    for layout in HostLayout::ALL {
+        for c in printable_ascii() {
+            assert!(layout.stroke(c).is_ok(), "{:?} {:?}", layout, c);
+        }
+    }
+}
+
+#[test]
+fn one_character_per_stroke() {
+    for layout in HostLayout::ALL {
+        let mut strokes: Vec<(Stroke, char)> = Vec::new();
+        for c in printable_ascii().chain("£€§°µ²³¬¦´¨¤ßäöüÄÖÜéèçàù".chars())
+        {
+            if let Ok(stroke) = layout.stroke(c) {
+                if let Some((_, other)) = strokes.iter().find(|(s, _)| *s == stroke) {
+                    panic!(
+                        "{:?} types {:?} and {:?} with {:?}",
+                        layout, other, c, stroke
+                    );
+                }
+                strokes.push((stroke, c));
+            }
+        }
+    }
+}
+
+#[test]
+fn unsupported_characters() {
+    assert_eq!(
+        HostLayout::Us.stroke('£'),
+        Err(TextError::UnsupportedChar('£'))
+    );
+    assert_eq!(
+        HostLayout::Fr.stroke('ü'),
+        Err(TextError::UnsupportedChar('ü'))
+    );
+    for layout in HostLayout::ALL {
+        assert_eq!(layout.check("a → b"), Err(TextError::UnsupportedChar('→')));
+    }
+}
+
+#[test]
+fn type_text() {
+    let mut keyboard = basic_keyboard([KeyAction::NoOp; 3]);
+    keyboard.set_host_layout(HostLayout::De);
+
+    keyboard.type_text("y@").unwrap();
+    update(&mut keyboard, 0, &[]);
+    let reports: Vec<_> = reports(&mut keyboard, 0)
+        .into_iter()
+        .map(|(_, modifiers, keycodes)| (modifiers, keycodes))
+        .collect();
+    assert_eq!(
+        reports,
+        [
+            (NONE, vec![KeyCode::Z]),
+            (ALT_GR, vec![KeyCode::Q]),
+            (NONE, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn dead_key_followed_by_space() {
+    let mut keyboard = basic_keyboard([KeyAction::NoOp; 3]);
+    keyboard.set_host_layout(HostLayout::De);
+
+    keyboard.type_text("^").unwrap();
+    let keys: Vec<_> = reports(&mut keyboard, 0)
+        .into_iter()
+        .map(|(_, _, keycodes)| keycodes)
+        .collect();
+    assert_eq!(
+        keys,
+        [vec![KeyCode::Grave], vec![KeyCode::Spacebar], vec![],]
+    );
+}
+
+#[test]
+fn type_text_rejected() {
+    let mut keyboard = basic_keyboard([KeyAction::NoOp; 3]);
+    keyboard.set_host_layout(HostLayout::Us);
+
+    assert_eq!(
+        keyboard.type_text("5 µs"),
+        Err(TextError::UnsupportedChar('µ'))
+    );
+    assert!(!keyboard.has_report());
+
+    keyboard.set_host_layout(HostLayout::De);
+    assert_eq!(keyboard.type_text("5 µs"), Ok(()));
+}
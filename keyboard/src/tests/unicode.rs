@@ -0,0 +1,153 @@
+extern crate std;
+
+use super::{basic_keyboard, reports, update};
+use crate::keycode::KeyCode;
+use crate::unicode::sequence;
+use crate::{HostLayout, KeyAction, Output, UnicodeMode};
+use std::{vec, vec::Vec};
+
+fn taps(codes: &[KeyCode]) -> Vec<Output> {
+    codes
+        .iter()
+        .flat_map(|&c| [Output::Press(c), Output::Release(c)])
+        .collect()
+}
+
+fn shifted(code: KeyCode) -> [Output; 4] {
+    [
+        Output::Press(KeyCode::LeftShift),
+        Output::Press(code),
+        Output::Release(code),
+        Output::Release(KeyCode::LeftShift),
+    ]
+}
+
+#[test]
+fn ibus() {
+    let mut expected = vec![
+        Output::Press(KeyCode::LeftControl),
+        Output::Press(KeyCode::LeftShift),
+        Output::Press(KeyCode::U),
+        Output::Release(KeyCode::U),
+        Output::Release(KeyCode::LeftShift),
+        Output::Release(KeyCode::LeftControl),
+    ];
+    expected.extend(taps(&[
+        KeyCode::Kb2,
+        KeyCode::Kb1,
+        KeyCode::Kb9,
+        KeyCode::Kb2,
+        KeyCode::Spacebar,
+    ]));
+
+    assert_eq!(
+        sequence('\u{2192}', UnicodeMode::IBus, HostLayout::Us).as_slice(),
+        expected
+    );
+}
+
+#[test]
+fn ibus_host_layout_digits() {
+    //digits are shifted on AZERTY
+    let digits: Vec<Output> = sequence('\u{b5}', UnicodeMode::IBus, HostLayout::Fr)
+        .into_iter()
+        .skip(6)
+        .collect();
+
+    let mut expected = Vec::new();
+    expected.extend(shifted(KeyCode::Kb0));
+    expected.extend(shifted(KeyCode::Kb0));
+    expected.extend(taps(&[KeyCode::B]));
+    expected.extend(shifted(KeyCode::Kb5));
+    expected.extend(taps(&[KeyCode::Spacebar]));
+    assert_eq!(digits, expected);
+}
+
+#[test]
+fn win_compose() {
+    let expected = taps(&[
+        KeyCode::RightAlt,
+        KeyCode::U,
+        KeyCode::Kb2,
+        KeyCode::Kb1,
+        KeyCode::Kb9,
+        KeyCode::Kb2,
+        KeyCode::Enter,
+    ]);
+
+    assert_eq!(
+        sequence('\u{2192}', UnicodeMode::WinCompose, HostLayout::Us).as_slice(),
+        expected
+    );
+}
+
+#[test]
+fn alt_code() {
+    let mut expected = vec![Output::Press(KeyCode::LeftAlt)];
+    expected.extend(taps(&[
+        KeyCode::KpPlus,
+        KeyCode::Kp0,
+        KeyCode::Kp0,
+        KeyCode::B,
+        KeyCode::Kp5,
+    ]));
+    expected.push(Output::Release(KeyCode::LeftAlt));
+
+    assert_eq!(
+        sequence('\u{b5}', UnicodeMode::AltCode, HostLayout::Us).as_slice(),
+        expected
+    );
+}
+
+#[test]
+fn mac_os_surrogate_pair() {
+    //U+1F600 is d83d de00 in UTF-16
+    let mut expected = vec![Output::Press(KeyCode::LeftAlt)];
+    expected.extend(taps(&[
+        KeyCode::D,
+        KeyCode::Kb8,
+        KeyCode::Kb3,
+        KeyCode::D,
+        KeyCode::D,
+        KeyCode::E,
+        KeyCode::Kb0,
+        KeyCode::Kb0,
+    ]));
+    expected.push(Output::Release(KeyCode::LeftAlt));
+
+    //Unicode Hex Input is laid out as US whatever the host layout
+    assert_eq!(
+        sequence('\u{1f600}', UnicodeMode::MacOs, HostLayout::Fr).as_slice(),
+        expected
+    );
+}
+
+#[test]
+fn long_codes_not_padded() {
+    let digits = sequence('\u{1f600}', UnicodeMode::WinCompose, HostLayout::Us);
+    //compose, u, five digits and enter
+    assert_eq!(digits.len(), 8 * 2);
+}
+
+#[test]
+fn switch_mode_at_runtime() {
+    let mut keyboard = basic_keyboard([
+        KeyAction::Unicode('\u{2192}'),
+        KeyAction::UnicodeMode(UnicodeMode::MacOs),
+        KeyAction::NoOp,
+    ]);
+    assert_eq!(keyboard.unicode_mode(), UnicodeMode::IBus);
+
+    update(&mut keyboard, 0, &[(0, true), (0, false)]);
+    let first = reports(&mut keyboard, 0);
+    assert!(first[0].2.contains(&KeyCode::U));
+
+    //the mode changes in order with the characters queued around it
+    update(&mut keyboard, 100, &[(1, true), (1, false), (0, true)]);
+    let second = reports(&mut keyboard, 100);
+    assert_eq!(keyboard.unicode_mode(), UnicodeMode::MacOs);
+    assert_eq!(second[0].2, [KeyCode::Kb2]);
+
+    keyboard.set_unicode_mode(UnicodeMode::WinCompose);
+    assert_eq!(keyboard.unicode_mode(), UnicodeMode::WinCompose);
+}
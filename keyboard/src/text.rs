@@ -0,0 +1,322 @@
+use crate::keycode::{KeyCode, Modifiers};
+
+const LETTERS: [KeyCode; 26] = [
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+];
+
+//'1' to '9' then '0', in key order
+const DIGITS: [KeyCode; 10] = [
+    KeyCode::Kb1,
+    KeyCode::Kb2,
+    KeyCode::Kb3,
+    KeyCode::Kb4,
+    KeyCode::Kb5,
+    KeyCode::Kb6,
+    KeyCode::Kb7,
+    KeyCode::Kb8,
+    KeyCode::Kb9,
+    KeyCode::Kb0,
+];
+
+/// Keyboard layout the host is set to, which decides the keys that type each character
+///
+/// Tables follow the Windows layouts, where some characters are dead keys.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum HostLayout {
+    #[default]
+    Us,
+    Uk,
+    De,
+    Fr,
+}
+
+/// Key press that types a character
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Stroke {
+    pub modifiers: Modifiers,
+    pub code: KeyCode,
+    /// Dead key, followed by a space to type the character on its own
+    pub dead: bool,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TextError {
+    /// The host layout has no keys for the character
+    UnsupportedChar(char),
+    /// Too much waiting to be reported to take more text
+    QueueFull,
+}
+
+const fn plain(code: KeyCode) -> Stroke {
+    Stroke {
+        modifiers: Modifiers::empty(),
+        code,
+        dead: false,
+    }
+}
+
+const fn shift(code: KeyCode) -> Stroke {
+    Stroke {
+        modifiers: Modifiers::SHIFT_LEFT,
+        code,
+        dead: false,
+    }
+}
+
+const fn alt_gr(code: KeyCode) -> Stroke {
+    Stroke {
+        modifiers: Modifiers::ALT_RIGHT,
+        code,
+        dead: false,
+    }
+}
+
+const fn dead(stroke: Stroke) -> Stroke {
+    Stroke {
+        dead: true,
+        ..stroke
+    }
+}
+
+impl HostLayout {
+    pub const ALL: [HostLayout; 4] = [
+        HostLayout::Us,
+        HostLayout::Uk,
+        HostLayout::De,
+        HostLayout::Fr,
+    ];
+
+    /// Keys that type a character
+    pub fn stroke(self, c: char) -> Result<Stroke, TextError> {
+        let stroke = match c {
+            'a'..='z' => Some(plain(self.letter(c))),
+            'A'..='Z' => Some(shift(self.letter(c.to_ascii_lowercase()))),
+            '1'..='9' | '0' => {
+                let code = DIGITS[(c as usize + 10 - '1' as usize) % 10];
+                Some(match self {
+                    HostLayout::Fr => shift(code),
+                    _ => plain(code),
+                })
+            }
+            '\n' => Some(plain(KeyCode::Enter)),
+            '\t' => Some(plain(KeyCode::Tab)),
+            ' ' => Some(plain(KeyCode::Spacebar)),
+            _ => match self {
+                HostLayout::Us => us(c),
+                HostLayout::Uk => uk(c),
+                HostLayout::De => de(c),
+                HostLayout::Fr => fr(c),
+            },
+        };
+        stroke.ok_or(TextError::UnsupportedChar(c))
+    }
+
+    /// Check every character of some text can be typed
+    pub fn check(self, text: &str) -> Result<(), TextError> {
+        text.chars().try_for_each(|c| self.stroke(c).map(|_| ()))
+    }
+
+    fn letter(self, c: char) -> KeyCode {
+        match (self, c) {
+            (HostLayout::De, 'y') => KeyCode::Z,
+            (HostLayout::De, 'z') => KeyCode::Y,
+            (HostLayout::Fr, 'a') => KeyCode::Q,
+            (HostLayout::Fr, 'q') => KeyCode::A,
+            (HostLayout::Fr, 'w') => KeyCode::Z,
+            (HostLayout::Fr, 'z') => KeyCode::W,
+            (HostLayout::Fr, 'm') => KeyCode::Semicolon,
+            _ => LETTERS[c as usize - 'a' as usize],
+        }
+    }
+}
+
+fn us(c: char) -> Option<Stroke> {
+    let stroke = match c {
+        '!' => shift(KeyCode::Kb1),
+        '@' => shift(KeyCode::Kb2),
+        '#' => shift(KeyCode::Kb3),
+        '$' => shift(KeyCode::Kb4),
+        '%' => shift(KeyCode::Kb5),
+        '^' => shift(KeyCode::Kb6),
+        '&' => shift(KeyCode::Kb7),
+        '*' => shift(KeyCode::Kb8),
+        '(' => shift(KeyCode::Kb9),
+        ')' => shift(KeyCode::Kb0),
+        '-' => plain(KeyCode::Minus),
+        '_' => shift(KeyCode::Minus),
+        '=' => plain(KeyCode::Equals),
+        '+' => shift(KeyCode::Equals),
+        '[' => plain(KeyCode::LeftBracket),
+        '{' => shift(KeyCode::LeftBracket),
+        ']' => plain(KeyCode::RightBracket),
+        '}' => shift(KeyCode::RightBracket),
+        '\\' => plain(KeyCode::BackslashANSI),
+        '|' => shift(KeyCode::BackslashANSI),
+        ';' => plain(KeyCode::Semicolon),
+        ':' => shift(KeyCode::Semicolon),
+        '\'' => plain(KeyCode::Apostrophy),
+        '"' => shift(KeyCode::Apostrophy),
+        '`' => plain(KeyCode::Grave),
+        '~' => shift(KeyCode::Grave),
+        ',' => plain(KeyCode::Comma),
+        '<' => shift(KeyCode::Comma),
+        '.' => plain(KeyCode::Dot),
+        '>' => shift(KeyCode::Dot),
+        '/' => plain(KeyCode::ForwardSlash),
+        '?' => shift(KeyCode::ForwardSlash),
+        _ => return None,
+    };
+    Some(stroke)
+}
+
+fn uk(c: char) -> Option<Stroke> {
+    let stroke = match c {
+        '"' => shift(KeyCode::Kb2),
+        '£' => shift(KeyCode::Kb3),
+        '€' => alt_gr(KeyCode::Kb4),
+        '\'' => plain(KeyCode::Apostrophy),
+        '@' => shift(KeyCode::Apostrophy),
+        '#' => plain(KeyCode::Hash),
+        '~' => shift(KeyCode::Hash),
+        '\\' => plain(KeyCode::BackslashISO),
+        '|' => shift(KeyCode::BackslashISO),
+        '`' => plain(KeyCode::Grave),
+        '¬' => shift(KeyCode::Grave),
+        '¦' => alt_gr(KeyCode::Grave),
+        //the rest of the printable ASCII keys match US
+        '!' | '$' | '%' | '^' | '&' | '*' | '(' | ')' | '-' | '_' | '=' | '+' | '[' | '{' | ']'
+        | '}' | ';' | ':' | ',' | '<' | '.' | '>' | '/' | '?' => return us(c),
+        _ => return None,
+    };
+    Some(stroke)
+}
+
+fn de(c: char) -> Option<Stroke> {
+    let stroke = match c {
+        '!' => shift(KeyCode::Kb1),
+        '"' => shift(KeyCode::Kb2),
+        '²' => alt_gr(KeyCode::Kb2),
+        '§' => shift(KeyCode::Kb3),
+        '³' => alt_gr(KeyCode::Kb3),
+        '$' => shift(KeyCode::Kb4),
+        '%' => shift(KeyCode::Kb5),
+        '&' => shift(KeyCode::Kb6),
+        '/' => shift(KeyCode::Kb7),
+        '{' => alt_gr(KeyCode::Kb7),
+        '(' => shift(KeyCode::Kb8),
+        '[' => alt_gr(KeyCode::Kb8),
+        ')' => shift(KeyCode::Kb9),
+        ']' => alt_gr(KeyCode::Kb9),
+        '=' => shift(KeyCode::Kb0),
+        '}' => alt_gr(KeyCode::Kb0),
+        'ß' => plain(KeyCode::Minus),
+        '?' => shift(KeyCode::Minus),
+        '\\' => alt_gr(KeyCode::Minus),
+        '´' => dead(plain(KeyCode::Equals)),
+        '`' => dead(shift(KeyCode::Equals)),
+        'ü' => plain(KeyCode::LeftBracket),
+        'Ü' => shift(KeyCode::LeftBracket),
+        '+' => plain(KeyCode::RightBracket),
+        '*' => shift(KeyCode::RightBracket),
+        '~' => alt_gr(KeyCode::RightBracket),
+        'ö' => plain(KeyCode::Semicolon),
+        'Ö' => shift(KeyCode::Semicolon),
+        'ä' => plain(KeyCode::Apostrophy),
+        'Ä' => shift(KeyCode::Apostrophy),
+        '#' => plain(KeyCode::Hash),
+        '\'' => shift(KeyCode::Hash),
+        '^' => dead(plain(KeyCode::Grave)),
+        '°' => shift(KeyCode::Grave),
+        '<' => plain(KeyCode::BackslashISO),
+        '>' => shift(KeyCode::BackslashISO),
+        '|' => alt_gr(KeyCode::BackslashISO),
+        ',' => plain(KeyCode::Comma),
+        ';' => shift(KeyCode::Comma),
+        '.' => plain(KeyCode::Dot),
+        ':' => shift(KeyCode::Dot),
+        '-' => plain(KeyCode::ForwardSlash),
+        '_' => shift(KeyCode::ForwardSlash),
+        '@' => alt_gr(KeyCode::Q),
+        '€' => alt_gr(KeyCode::E),
+        'µ' => alt_gr(KeyCode::M),
+        _ => return None,
+    };
+    Some(stroke)
+}
+
+fn fr(c: char) -> Option<Stroke> {
+    let stroke = match c {
+        '&' => plain(KeyCode::Kb1),
+        'é' => plain(KeyCode::Kb2),
+        '~' => dead(alt_gr(KeyCode::Kb2)),
+        '"' => plain(KeyCode::Kb3),
+        '#' => alt_gr(KeyCode::Kb3),
+        '\'' => plain(KeyCode::Kb4),
+        '{' => alt_gr(KeyCode::Kb4),
+        '(' => plain(KeyCode::Kb5),
+        '[' => alt_gr(KeyCode::Kb5),
+        '-' => plain(KeyCode::Kb6),
+        '|' => alt_gr(KeyCode::Kb6),
+        'è' => plain(KeyCode::Kb7),
+        '`' => dead(alt_gr(KeyCode::Kb7)),
+        '_' => plain(KeyCode::Kb8),
+        '\\' => alt_gr(KeyCode::Kb8),
+        'ç' => plain(KeyCode::Kb9),
+        '^' => alt_gr(KeyCode::Kb9),
+        'à' => plain(KeyCode::Kb0),
+        '@' => alt_gr(KeyCode::Kb0),
+        ')' => plain(KeyCode::Minus),
+        '°' => shift(KeyCode::Minus),
+        ']' => alt_gr(KeyCode::Minus),
+        '=' => plain(KeyCode::Equals),
+        '+' => shift(KeyCode::Equals),
+        '}' => alt_gr(KeyCode::Equals),
+        '¨' => dead(shift(KeyCode::LeftBracket)),
+        '$' => plain(KeyCode::RightBracket),
+        '£' => shift(KeyCode::RightBracket),
+        '¤' => alt_gr(KeyCode::RightBracket),
+        'ù' => plain(KeyCode::Apostrophy),
+        '%' => shift(KeyCode::Apostrophy),
+        '²' => plain(KeyCode::Grave),
+        '*' => plain(KeyCode::Hash),
+        'µ' => shift(KeyCode::Hash),
+        '<' => plain(KeyCode::BackslashISO),
+        '>' => shift(KeyCode::BackslashISO),
+        ',' => plain(KeyCode::M),
+        '?' => shift(KeyCode::M),
+        ';' => plain(KeyCode::Comma),
+        '.' => shift(KeyCode::Comma),
+        ':' => plain(KeyCode::Dot),
+        '/' => shift(KeyCode::Dot),
+        '!' => plain(KeyCode::ForwardSlash),
+        '§' => shift(KeyCode::ForwardSlash),
+        '€' => alt_gr(KeyCode::E),
+        _ => return None,
+    };
+    Some(stroke)
+}
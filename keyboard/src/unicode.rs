@@ -0,0 +1,95 @@
+use crate::keycode::KeyCode;
+use crate::macros::{tap, Sequence};
+use crate::text::HostLayout;
+use crate::Output;
+
+/// How the host turns a typed hex code into a character
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum UnicodeMode {
+    /// Linux IBus, Ctrl+Shift+U then the hex code and space
+    #[default]
+    IBus,
+    /// Windows WinCompose, the compose key (right alt) then u, the hex code and enter
+    WinCompose,
+    /// Windows Alt code, hold alt and type keypad plus then the hex code, needs
+    /// `EnableHexNumpad` set in the registry
+    AltCode,
+    /// macOS Unicode Hex Input, hold option and type the hex code of each UTF-16 unit
+    MacOs,
+}
+
+const HEX: [char; 16] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
+];
+
+const KEYPAD_DIGITS: [KeyCode; 10] = [
+    KeyCode::Kp0,
+    KeyCode::Kp1,
+    KeyCode::Kp2,
+    KeyCode::Kp3,
+    KeyCode::Kp4,
+    KeyCode::Kp5,
+    KeyCode::Kp6,
+    KeyCode::Kp7,
+    KeyCode::Kp8,
+    KeyCode::Kp9,
+];
+
+/// Outputs that type a character with an input method
+pub(crate) fn sequence(c: char, mode: UnicodeMode, layout: HostLayout) -> Sequence {
+    let mut sequence = Sequence::new();
+    let out = &mut sequence;
+
+    match mode {
+        UnicodeMode::IBus => {
+            out.push(Output::Press(KeyCode::LeftControl));
+            out.push(Output::Press(KeyCode::LeftShift));
+            tap(out, KeyCode::U);
+            out.push(Output::Release(KeyCode::LeftShift));
+            out.push(Output::Release(KeyCode::LeftControl));
+            hex(out, c as u32, 4, |out, digit| type_char(out, layout, digit));
+            tap(out, KeyCode::Spacebar);
+        }
+        UnicodeMode::WinCompose => {
+            tap(out, KeyCode::RightAlt);
+            type_char(out, layout, 'u');
+            hex(out, c as u32, 4, |out, digit| type_char(out, layout, digit));
+            tap(out, KeyCode::Enter);
+        }
+        UnicodeMode::AltCode => {
+            out.push(Output::Press(KeyCode::LeftAlt));
+            tap(out, KeyCode::KpPlus);
+            hex(out, c as u32, 4, |out, digit| match digit.to_digit(10) {
+                Some(n) => tap(out, KEYPAD_DIGITS[n as usize]),
+                None => type_char(out, layout, digit),
+            });
+            out.push(Output::Release(KeyCode::LeftAlt));
+        }
+        UnicodeMode::MacOs => {
+            //Unicode Hex Input is an input source of its own, laid out as US
+            out.push(Output::Press(KeyCode::LeftAlt));
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                hex(out, *unit as u32, 4, |out, digit| {
+                    type_char(out, HostLayout::Us, digit)
+                });
+            }
+            out.push(Output::Release(KeyCode::LeftAlt));
+        }
+    }
+    sequence
+}
+
+//type a code in lowercase hex, zero padded to at least `min` digits
+fn hex(out: &mut Sequence, code: u32, min: u32, mut digit: impl FnMut(&mut Sequence, char)) {
+    let digits = (8 - code.leading_zeros() / 4).max(min);
+    for i in (0..digits).rev() {
+        digit(out, HEX[(code >> (i * 4)) as usize & 0xf]);
+    }
+}
+
+fn type_char(out: &mut Sequence, layout: HostLayout, c: char) {
+    //every layout can type hex digits
+    if let Ok(stroke) = layout.stroke(c) {
+        crate::macros::stroke(out, stroke);
+    }
+}